@@ -23,7 +23,6 @@ pub struct LinkedList<T> {
 #[manual_init]
 struct Link<T> {
     #[init]
-    #[uninit = MaybeUninit::<NonNull::<LinkedList::<T>>>]
     ptr: NonNull<LinkedList<T>>,
 }
 
@@ -105,11 +104,8 @@ impl<T> PinnedInit for LinkedListUninit<T> {
     type Param = ();
 
     fn init_raw(mut this: NeedsPinnedInit<Self>, _: Self::Param) {
-        let link = {
-            // SAFETY: the pointer from NeedsPinnedInit is valid and we do not use it until we are
-            // initialized.
-            this.as_ptr_mut() as *mut LinkedList<T>
-        };
+        // we do not use the pointer until we are initialized.
+        let link = this.self_ref().as_mut_ptr();
         let LinkedListOngoingInit {
             prev,
             next,
@@ -0,0 +1,162 @@
+#![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+#![deny(unsafe_op_in_unsafe_fn)]
+// An intrusive circular doubly linked list. Every entry embeds its `Links` in an
+// `UnsafeAliasCell`, so its neighbours are allowed to point to (and modify) them.
+use core::{marker::PhantomData, mem::MaybeUninit, pin::Pin, ptr};
+use pinned_init::{prelude::*, stack_init};
+use unsafe_alias_cell::UnsafeAliasCell;
+
+#[manual_init(pinned, pin_project(PinnedDrop))]
+pub struct Links {
+    #[init]
+    prev: *mut Links,
+    #[init]
+    next: *mut Links,
+}
+
+impl LinksUninit {
+    pub fn uninit() -> Self {
+        Self {
+            prev: MaybeUninit::uninit(),
+            next: MaybeUninit::uninit(),
+        }
+    }
+}
+
+impl PinnedInit for LinksUninit {
+    type Initialized = Links;
+    /// `None` creates a new list only containing these links, `Some(links)`
+    /// inserts them after `links`.
+    type Param = Option<*mut Links>;
+
+    fn init_raw(mut this: NeedsPinnedInit<Self>, after: Self::Param) {
+        // links are always stored inside of an `UnsafeAliasCell`, so we are
+        // allowed to hand out pointers to them.
+        let ptr = this.self_ref().as_mut_ptr();
+        let LinksOngoingInit { prev, next } = this.begin_init();
+        match after {
+            None => {
+                prev.init(ptr);
+                next.init(ptr);
+            }
+            Some(after) => unsafe {
+                // SAFETY: `after` and its successor are part of a valid list,
+                // they are stored inside of `UnsafeAliasCell`s.
+                let old_next = (*after).next;
+                prev.init(after);
+                next.init(old_next);
+                (*after).next = ptr;
+                (*old_next).prev = ptr;
+            },
+        }
+    }
+}
+
+#[pin_project::pinned_drop]
+impl PinnedDrop for Links {
+    fn drop(self: Pin<&mut Self>) {
+        let (prev, next) = (self.prev, self.next);
+        if ptr::eq(prev, &*self) {
+            // these links are the only ones in the list.
+            return;
+        }
+        unsafe {
+            // SAFETY: the neighbours are valid, because they remove themselves
+            // from the list when they are dropped.
+            (*prev).next = next;
+            (*next).prev = prev;
+        }
+    }
+}
+
+#[pin_project::pinned_drop]
+impl PinnedDrop for LinksUninit {
+    fn drop(self: Pin<&mut Self>) {}
+}
+
+#[pinned_init]
+pub struct List<T> {
+    #[init]
+    head: UnsafeAliasCell<Links>,
+    _t: PhantomData<T>,
+}
+
+impl<T> ListUninit<T> {
+    pub fn new() -> Self {
+        Self {
+            head: UnsafeAliasCell::new(LinksUninit::uninit()),
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for ListUninit<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> List<T> {
+    /// The parameters to insert a new entry at the front of this list.
+    pub fn push_front(self: Pin<&Self>) -> EntryInitParams {
+        EntryInitParams {
+            links: Some(self.head.get()),
+        }
+    }
+
+    pub fn for_each(self: Pin<&Self>, mut f: impl FnMut(&T)) {
+        let head = self.head.get();
+        let mut cur = unsafe {
+            // SAFETY: the head is initialized.
+            (*head).next
+        };
+        while cur != head {
+            unsafe {
+                // SAFETY: all links except the head are the first field of an
+                // `Entry<T>`, which is `repr(C)`.
+                f(&(*(cur as *const Entry<T>)).value);
+                cur = (*cur).next;
+            }
+        }
+    }
+}
+
+#[pinned_init]
+#[repr(C)]
+pub struct Entry<T> {
+    #[init]
+    links: UnsafeAliasCell<Links>,
+    value: T,
+}
+
+impl<T> EntryUninit<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            links: UnsafeAliasCell::new(LinksUninit::uninit()),
+            value,
+        }
+    }
+}
+
+fn main() {
+    let list = Box::pin(ListUninit::<i32>::new()).init_with(ListInitParams { head: None });
+    let a = Box::pin(EntryUninit::new(1)).init_with(list.as_ref().push_front());
+    let mut values = vec![];
+    {
+        stack_init!(let _b = EntryUninit::new(2), list.as_ref().push_front());
+        let c = Box::pin(EntryUninit::new(3)).init_with(list.as_ref().push_front());
+        list.as_ref().for_each(|v| values.push(*v));
+        assert_eq!(values, [3, 2, 1]);
+        drop(c);
+        values.clear();
+        list.as_ref().for_each(|v| values.push(*v));
+        assert_eq!(values, [2, 1]);
+    }
+    values.clear();
+    list.as_ref().for_each(|v| values.push(*v));
+    assert_eq!(values, [1]);
+    drop(a);
+    values.clear();
+    list.as_ref().for_each(|v| values.push(*v));
+    assert!(values.is_empty());
+}
@@ -5,6 +5,7 @@ use core::{
     cell::UnsafeCell,
     mem::MaybeUninit,
     ops::{Deref, DerefMut},
+    ptr::addr_of_mut,
 };
 use pinned_init::prelude::*;
 
@@ -20,40 +21,57 @@ impl RawMutex {
     unsafe fn unlock(_: *mut RawMutex) {}
 }
 
-impl<T> MutexUninit<T> {
-    pub fn new(value: T) -> Self {
-        Self {
-            inner: MaybeUninit::uninit(),
-            value: UnsafeCell::new(value),
-        }
-    }
-}
-
 #[manual_init(pinned)]
-pub struct Mutex<T> {
+pub struct RawMutexWrapper {
     #[pin]
     #[init]
-    #[uninit = MaybeUninit::<UnsafeCell<RawMutex>>]
-    inner: UnsafeCell<RawMutex>,
-    value: UnsafeCell<T>,
+    #[uninit = MaybeUninit::<RawMutex>]
+    raw: RawMutex,
 }
 
-impl<T> PinnedInit for MutexUninit<T> {
-    type Initialized = Mutex<T>;
+impl PinnedInit for RawMutexWrapperUninit {
+    type Initialized = RawMutexWrapper;
     type Param = ();
 
     fn init_raw(this: NeedsPinnedInit<Self>, _: ()) {
-        let MutexOngoingInit { mut inner, .. } = this.begin_init();
+        let RawMutexWrapperOngoingInit { mut raw } = this.begin_init();
         unsafe {
             // SAFETY: FFI call initializes the raw mutex
-            let ptr = inner.as_ptr_mut();
-            (*ptr).write(UnsafeCell::new(RawMutex::new()));
-            RawMutex::init((*ptr).assume_init_mut().get());
-            inner.assume_init();
+            let ptr = raw.as_ptr_mut();
+            (*ptr).write(RawMutex::new());
+            RawMutex::init((*ptr).as_mut_ptr());
+            raw.assume_init();
+        }
+    }
+}
+
+impl RawMutexWrapper {
+    fn raw(this: &UnsafeCell<Self>) -> *mut RawMutex {
+        unsafe {
+            // SAFETY: the pointer from the `UnsafeCell` is valid
+            addr_of_mut!((*this.get()).raw)
+        }
+    }
+}
+
+impl<T> MutexUninit<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: UnsafeCell::new(RawMutexWrapperUninit {
+                raw: MaybeUninit::uninit(),
+            }),
+            value: UnsafeCell::new(value),
         }
     }
 }
 
+#[pinned_init]
+pub struct Mutex<T> {
+    #[init]
+    inner: UnsafeCell<RawMutexWrapper>,
+    value: UnsafeCell<T>,
+}
+
 pub struct Guard<'a, T> {
     mutex: &'a Mutex<T>,
 }
@@ -82,7 +100,7 @@ impl<'a, T> Drop for Guard<'a, T> {
     fn drop(&mut self) {
         unsafe {
             // SAFETY: FFI call on valid pointer
-            RawMutex::unlock(self.mutex.inner.get());
+            RawMutex::unlock(RawMutexWrapper::raw(&self.mutex.inner));
         }
     }
 }
@@ -91,7 +109,7 @@ impl<T> Mutex<T> {
     pub fn lock(&self) -> Guard<'_, T> {
         unsafe {
             // SAFETY: FFI call on valid pointer
-            RawMutex::lock(self.inner.get());
+            RawMutex::lock(RawMutexWrapper::raw(&self.inner));
         }
         Guard { mutex: self }
     }
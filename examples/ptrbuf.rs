@@ -7,7 +7,7 @@ use core::{
     pin::Pin,
     ptr::addr_of,
 };
-use pinned_init::prelude::*;
+use pinned_init::{needs_init::SelfRef, prelude::*};
 
 #[repr(transparent)]
 struct UnsafeAliasCell<T> {
@@ -31,10 +31,8 @@ impl<T> UnsafeAliasCell<T> {
 #[manual_init(pinned, pin_project(PinnedDrop))]
 pub struct PtrBuf<T, const N: usize> {
     #[init]
-    #[uninit = MaybeUninit::<*const T>]
     idx: *const T,
     #[init]
-    #[uninit = MaybeUninit::<*const T>]
     end: *const T,
     #[pin]
     buf: UnsafeAliasCell<[ManuallyDrop<T>; N]>,
@@ -46,10 +44,10 @@ impl<T, const N: usize> PinnedInit for PtrBufUninit<T, N> {
 
     fn init_raw(this: NeedsPinnedInit<Self>, _: Self::Param) {
         let PtrBufOngoingInit { idx, end, buf } = this.begin_init();
-        let ptr = unsafe { addr_of!(*buf.get()) as *const T };
-        idx.init(ptr);
-        let ptr = unsafe { addr_of!((*buf.get())[N - 1]) as *const ManuallyDrop<T> as *const T };
-        end.init(ptr);
+        // `ManuallyDrop<T>` has the same layout as `T`.
+        let start = SelfRef::new(buf).cast::<T>().as_ptr();
+        idx.init(start);
+        end.init(start.wrapping_add(N - 1));
     }
 }
 
@@ -1,7 +1,8 @@
 use proc_macro2::*;
+use proc_macro_error::*;
 use quote::*;
 use std::collections::*;
-use syn::{parse::*, *};
+use syn::{ext::IdentExt, parse::*, *};
 
 pub fn has_outer_attr<'a>(attrs: impl IntoIterator<Item = &'a Attribute>, name: &str) -> bool {
     attrs
@@ -65,6 +66,8 @@ pub enum ManualInitParam {
     /// Delegates the token stream to the pin_project invokation, only makes sense if also
     /// ManualInitParam::Pinned
     PinProject(TokenStream),
+    /// Implements PinnedDeinit, only makes sense if also ManualInitParam::Pinned
+    Deinit,
 }
 
 pub fn parse_attrs(stream: ParseStream) -> syn::parse::Result<Vec<ManualInitParam>> {
@@ -78,6 +81,7 @@ pub fn parse_attrs(stream: ParseStream) -> syn::parse::Result<Vec<ManualInitPara
                     match cursor.ident() {
                         Some((ident, next)) => match format!("{ident}").as_ref() {
                             "pinned" => Ok((Some(ManualInitParam::Pinned), next)),
+                            "deinit" => Ok((Some(ManualInitParam::Deinit), next)),
                             "pin_project" => {
                                 if let Some((inner, _, next)) = next.group(Delimiter::Parenthesis) {
                                     Ok((
@@ -88,9 +92,13 @@ pub fn parse_attrs(stream: ParseStream) -> syn::parse::Result<Vec<ManualInitPara
                                     Err(cursor.error("Expected `pin_project(<args>)`."))
                                 }
                             }
-                            _ => Err(cursor.error("Expected `pinned` or `pin_project(<args>)`.")),
+                            _ => Err(cursor
+                                .error("Expected `pinned`, `deinit` or `pin_project(<args>)`.")),
                         },
-                        _ => Err(cursor.error("Expected `pinned` or `pin_project(<args>)`.")),
+                        _ => {
+                            Err(cursor
+                                .error("Expected `pinned`, `deinit` or `pin_project(<args>)`."))
+                        }
                     }
                 }
             })? {
@@ -110,3 +118,395 @@ pub fn parse_attrs(stream: ParseStream) -> syn::parse::Result<Vec<ManualInitPara
         })?;
     }
 }
+
+/// removes the `param = { .. }` (or `param = ( .. )`) argument from the
+/// arguments of `#[pinned_init]`, returns the remaining arguments and the
+/// additional members of the init params given by it.
+pub fn split_param_arg(attr: TokenStream) -> (TokenStream, Option<Fields>) {
+    let mut args = vec![vec![]];
+    for tt in attr {
+        match tt {
+            TokenTree::Punct(p) if p.as_char() == ',' => args.push(vec![]),
+            tt => args.last_mut().unwrap().push(tt),
+        }
+    }
+    let mut rest = vec![];
+    let mut params: Option<Fields> = None;
+    for arg in args.into_iter().filter(|arg| !arg.is_empty()) {
+        match &arg[..] {
+            [TokenTree::Ident(ident), ..] if ident == "param" => {
+                let fields = match &arg[1..] {
+                    [TokenTree::Punct(eq), TokenTree::Group(group)]
+                        if eq.as_char() == '='
+                            && matches!(
+                                group.delimiter(),
+                                Delimiter::Brace | Delimiter::Parenthesis
+                            ) =>
+                    {
+                        let tokens = TokenTree::Group(group.clone()).into();
+                        let fields = if group.delimiter() == Delimiter::Brace {
+                            parse2(tokens).map(Fields::Named)
+                        } else {
+                            parse2(tokens).map(Fields::Unnamed)
+                        };
+                        match fields {
+                            Ok(fields) => fields,
+                            Err(e) => {
+                                emit_error!(e.span(), "{}", e);
+                                continue;
+                            }
+                        }
+                    }
+                    _ => {
+                        emit_error!(
+                            ident,
+                            "Expected `param = { <fields> }` or `param = ( <types> )`."
+                        );
+                        continue;
+                    }
+                };
+                if params.is_some() {
+                    emit_error!(ident, "`param` should only be specified once.");
+                }
+                // the members of the init params are public
+                params = Some(map_fields(fields, |mut f| {
+                    f.vis = parse_quote! { pub };
+                    f
+                }));
+            }
+            _ => rest.push(arg.into_iter().collect::<TokenStream>()),
+        }
+    }
+    (quote! { #(#rest),* }, params)
+}
+
+/// returns the member used to access the `i`th field, this is the name of the
+/// field for named fields and the index for unnamed fields.
+pub fn field_member(i: usize, field: &Field) -> Member {
+    match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index {
+            index: i.try_into().unwrap(),
+            span: Span::call_site(),
+        }),
+    }
+}
+
+/// returns an identifier for the `i`th field, used to name fields derived from
+/// it (e.g. the initialization flags), unnamed fields are named `__field{i}`.
+pub fn field_ident(i: usize, field: &Field) -> Ident {
+    match &field.ident {
+        Some(ident) => ident.clone(),
+        None => format_ident!("__field{}", i),
+    }
+}
+
+/// returns the name of the `i`th field in the expressions given via
+/// `#[init(...)]`, unnamed fields are named `field{i}`.
+pub fn scope_ident(i: usize, field: &Field) -> Ident {
+    match &field.ident {
+        Some(ident) => ident.clone(),
+        None => format_ident!("field{}", i),
+    }
+}
+
+/// returns the hygienic identifier used to bind the field named `ident` in the
+/// generated code, it cannot be shadowed by (or shadow) user provided names.
+pub fn hygienic_field_ident(ident: &Ident) -> Ident {
+    format_ident!("__field_{}", ident.unraw(), span = Span::mixed_site())
+}
+
+/// applies `map` to every field and keeps the shape (named or unnamed) of the
+/// given fields.
+pub fn map_fields(fields: Fields, map: impl FnMut(Field) -> Field) -> Fields {
+    match fields {
+        Fields::Named(FieldsNamed { named, brace_token }) => Fields::Named(FieldsNamed {
+            named: named.into_iter().map(map).collect(),
+            brace_token,
+        }),
+        Fields::Unnamed(FieldsUnnamed {
+            unnamed,
+            paren_token,
+        }) => Fields::Unnamed(FieldsUnnamed {
+            unnamed: unnamed.into_iter().map(map).collect(),
+            paren_token,
+        }),
+        Fields::Unit => Fields::Unit,
+    }
+}
+
+/// produces the body of a struct definition (everything after the generics),
+/// the where clause is placed after the fields for tuple structs.
+pub fn struct_body(where_clause: impl ToTokens, fields: &Fields) -> TokenStream {
+    match fields {
+        Fields::Named(_) => quote! { #where_clause #fields },
+        _ => quote! { #fields #where_clause; },
+    }
+}
+
+/// returns an identifier for the `i`th field of the enum variant `variant`,
+/// used to name the initialization flags of enum fields.
+pub fn variant_field_ident(variant: &Ident, i: usize, field: &Field) -> Ident {
+    format_ident!("{}_{}", variant, field_ident(i, field))
+}
+
+/// returns the member of the init params of an enum holding the parameters of
+/// the variant `variant`, this is the `snake_case` name of the variant. Names
+/// that are keywords get a trailing underscore (`Move` -> `move_`).
+pub fn variant_params_ident(variant: &Ident) -> Ident {
+    let name = snake_case(&variant.unraw().to_string());
+    match parse_str::<Ident>(&name) {
+        Ok(ident) => ident,
+        Err(_) => format_ident!("{}_", name),
+    }
+}
+
+/// converts a `CamelCase` name into `snake_case`.
+fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut res = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            // start a new word after a lowercase letter or digit and at the
+            // last uppercase letter of an acronym (`HTTPState` -> `http_state`).
+            if i > 0
+                && chars[i - 1] != '_'
+                && (!chars[i - 1].is_uppercase()
+                    || chars.get(i + 1).is_some_and(|n| n.is_lowercase()))
+            {
+                res.push('_');
+            }
+            res.extend(c.to_lowercase());
+        } else {
+            res.push(*c);
+        }
+    }
+    res
+}
+
+/// returns the identifier used to bind the `i`th field of an enum variant in
+/// a pattern.
+pub fn binding_ident(i: usize) -> Ident {
+    format_ident!("__binding{}", i)
+}
+
+/// returns the name of the projection enum that `#[pin_project]` generates
+/// for the uninitialized variant of an enum.
+pub fn projection_ident(uninit_ident: &Ident) -> Ident {
+    format_ident!("__{}Projection", uninit_ident)
+}
+
+/// ensures that `generics` has a where clause that is empty or has a trailing
+/// comma and returns it, so further predicates can be appended.
+pub fn trailing_where_clause(generics: &mut Generics) -> TokenStream {
+    let where_clause = generics.make_where_clause();
+    if !where_clause.predicates.empty_or_trailing() {
+        where_clause.predicates.push_punct(Default::default());
+    }
+    // ensure that we have a `where`
+    if where_clause.predicates.is_empty() {
+        quote! {where}
+    } else {
+        quote! { #where_clause }
+    }
+}
+
+/// parses the `#[ongoing_init(<name>)]` attribute, aborts if it is missing.
+pub fn ongoing_init_path(attrs: &[Attribute], ident: &Ident) -> Path {
+    attrs
+        .iter()
+        .filter_map(|a| {
+            if let Ok(Meta::List(MetaList { path, nested, .. })) = a.parse_meta() {
+                if path.is_ident("ongoing_init") {
+                    if nested.len() == 1 {
+                        if let NestedMeta::Meta(Meta::Path(path)) = nested.first().unwrap() {
+                            return Some(path.clone());
+                        } else {
+                            emit_error!(nested, "Expected a path.");
+                        }
+                    } else {
+                        emit_error!(nested, "Expected single argument");
+                    }
+                }
+            }
+            None
+        })
+        .reduce(|a, b| {
+            emit_error!(b, "#[ongoing_init] should only be specified once."; note = SpanRange::from_tokens(&a).collapse() => "other #[ongoing_init] here");
+            a
+        }).unwrap_or_else(|| abort!(ident, "Expected #[ongoing_init(<name>)] attribute."))
+}
+
+/// collects all identifiers and lifetimes used in `tokens`.
+fn collect_idents(
+    tokens: TokenStream,
+    idents: &mut HashSet<String>,
+    lifetimes: &mut HashSet<String>,
+) {
+    let mut is_lifetime = false;
+    for tt in tokens {
+        match tt {
+            TokenTree::Group(g) => collect_idents(g.stream(), idents, lifetimes),
+            TokenTree::Ident(ident) if is_lifetime => {
+                lifetimes.insert(ident.to_string());
+            }
+            TokenTree::Ident(ident) => {
+                idents.insert(ident.to_string());
+            }
+            TokenTree::Punct(p) if p.as_char() == '\'' => {
+                is_lifetime = true;
+                continue;
+            }
+            _ => {}
+        }
+        is_lifetime = false;
+    }
+}
+
+/// returns only the generics used by `tokens`, because a struct may not have
+/// unused generics. Bounds and where predicates referring to the removed
+/// generics are removed as well.
+pub fn filter_generics(generics: &Generics, tokens: impl ToTokens) -> Generics {
+    let uses = |tokens: TokenStream| {
+        let mut idents = HashSet::new();
+        let mut lifetimes = HashSet::new();
+        collect_idents(tokens, &mut idents, &mut lifetimes);
+        move |param: &GenericParam| match param {
+            GenericParam::Type(t) => idents.contains(&t.ident.to_string()),
+            GenericParam::Lifetime(l) => lifetimes.contains(&l.lifetime.ident.to_string()),
+            GenericParam::Const(c) => idents.contains(&c.ident.to_string()),
+        }
+    };
+    let used = uses(tokens.into_token_stream());
+    let (kept, removed): (Vec<_>, Vec<_>) = generics.params.iter().cloned().partition(&used);
+    let uses_removed = |tokens: TokenStream| removed.iter().any(uses(tokens));
+    let params = kept
+        .into_iter()
+        .map(|mut param| {
+            match &mut param {
+                GenericParam::Type(t) => {
+                    t.bounds = t
+                        .bounds
+                        .iter()
+                        .filter(|b| !uses_removed(quote! { #b }))
+                        .cloned()
+                        .collect();
+                    if t.default
+                        .as_ref()
+                        .is_some_and(|d| uses_removed(quote! { #d }))
+                    {
+                        t.eq_token = None;
+                        t.default = None;
+                    }
+                }
+                GenericParam::Lifetime(l) => {
+                    l.bounds = l
+                        .bounds
+                        .iter()
+                        .filter(|b| !uses_removed(quote! { #b }))
+                        .cloned()
+                        .collect();
+                }
+                GenericParam::Const(_) => {}
+            }
+            param
+        })
+        .collect();
+    let where_clause = generics.where_clause.as_ref().map(|w| WhereClause {
+        where_token: w.where_token,
+        predicates: w
+            .predicates
+            .iter()
+            .filter(|p| !uses_removed(quote! { #p }))
+            .cloned()
+            .collect(),
+    });
+    Generics {
+        lt_token: generics.lt_token,
+        params,
+        gt_token: generics.gt_token,
+        where_clause,
+    }
+}
+
+/// the arguments of an `#[init(...)]` field attribute.
+#[derive(Default)]
+pub struct InitArgs {
+    /// `param = <expr>`, the expression used as the parameter of the field.
+    pub param: Option<Expr>,
+    /// `with = <expr>`, the function used to initialize the field.
+    pub with: Option<Expr>,
+    /// `unpinned`, the field is not structurally pinned and initialized via
+    /// `Init`.
+    pub unpinned: bool,
+}
+
+/// parses the arguments of the `#[init(...)]` attribute in `attrs`.
+pub fn parse_init_args(attrs: &[Attribute]) -> InitArgs {
+    let mut args = InitArgs::default();
+    for attr in attrs
+        .iter()
+        .filter(|a| matches!(a.style, AttrStyle::Outer) && a.path.is_ident("init"))
+    {
+        if attr.tokens.is_empty() {
+            continue;
+        }
+        let parsed = attr.parse_args_with(|stream: ParseStream| {
+            stream.parse_terminated::<_, Token![,]>(|stream: ParseStream| {
+                let ident = stream.parse::<Ident>()?;
+                if stream.peek(Token![=]) {
+                    stream.parse::<Token![=]>()?;
+                    Ok((ident, Some(stream.parse::<Expr>()?)))
+                } else {
+                    Ok((ident, None))
+                }
+            })
+        });
+        match parsed {
+            Ok(parsed) => {
+                for (ident, expr) in parsed {
+                    let slot = match (format!("{ident}").as_ref(), expr) {
+                        ("param", Some(expr)) => (&mut args.param, expr),
+                        ("with", Some(expr)) => (&mut args.with, expr),
+                        ("unpinned", None) => {
+                            if args.unpinned {
+                                emit_error!(ident, "`unpinned` should only be specified once.");
+                            }
+                            args.unpinned = true;
+                            continue;
+                        }
+                        _ => {
+                            emit_error!(
+                                ident,
+                                "Expected `param = <expr>`, `with = <expr>` or `unpinned`."
+                            );
+                            continue;
+                        }
+                    };
+                    if slot.0.is_some() {
+                        emit_error!(ident, "`{}` should only be specified once.", ident);
+                    }
+                    *slot.0 = Some(slot.1);
+                }
+            }
+            Err(e) => emit_error!(e.span(), "{}", e),
+        }
+    }
+    args
+}
+
+/// emits an error for every `#[init(...)]` attribute with arguments, these are
+/// only supported by `#[pinned_init]`.
+pub fn forbid_init_args<'a>(fields: impl IntoIterator<Item = &'a Field>) {
+    for attr in fields.into_iter().flat_map(|f| &f.attrs) {
+        if matches!(attr.style, AttrStyle::Outer)
+            && attr.path.is_ident("init")
+            && !attr.tokens.is_empty()
+        {
+            emit_error!(
+                attr,
+                "Arguments of #[init] are only supported by #[pinned_init]."
+            );
+        }
+    }
+}
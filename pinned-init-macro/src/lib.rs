@@ -1,81 +1,117 @@
 //! Proc macros for the `pinned_init` crate, see  [`macro@pinned_init`] and [`macro@manual_init`]
 //! for details.
 
-use crate::helpers::{has_outer_attr, my_split_for_impl, parse_attrs, ManualInitParam};
+use crate::helpers::{
+    binding_ident, field_ident, field_member, filter_generics, forbid_init_args, has_outer_attr,
+    hygienic_field_ident, map_fields, my_split_for_impl, ongoing_init_path, parse_attrs,
+    parse_init_args, projection_ident, scope_ident, split_param_arg, struct_body,
+    trailing_where_clause, variant_field_ident, variant_params_ident, InitArgs, ManualInitParam,
+};
 use proc_macro2::*;
 use proc_macro_error::*;
 use quote::*;
-use syn::{parse::*, *};
+use syn::{ext::IdentExt, parse::*, *};
 
 mod helpers;
 
-/// Use this attribute on a struct with named fields to ensure safe
-/// pinned initialization of all the fields marked with `#[init]`.
+/// Use this attribute on a struct with named or unnamed fields or on an enum to
+/// ensure safe pinned initialization of all the fields marked with `#[init]`.
 ///
 /// This attribute does several things, it:
-/// - `#[pin_project]`s your struct, structually pinning all fields with `#[init]` implicitly (adding `#[pin]`).
+/// - `#[pin_project]`s your struct, structually pinning all fields with `#[init]` implicitly (adding `#[pin]`),
+///   except for fields marked with `#[init(unpinned)]`, these are initialized via `Init`.
 /// - adds a constant type parameter of type bool with a default value of true.
-/// This constant type parameter indicates if your struct is in an initialized
-/// (and thus also pinned) state. A type alias `{your-struct-name}Uninit` is
-/// created to refer to the uninitialized variant more ergonomically, it should
-/// always be used instead of specifying the const parameter.
+///   This constant type parameter indicates if your struct is in an initialized
+///   (and thus also pinned) state. A type alias `{your-struct-name}Uninit` is
+///   created to refer to the uninitialized variant more ergonomically, it should
+///   always be used instead of specifying the const parameter.
 /// - propagates that const parameter to all fields marked with `#[init]`.
 /// - implements `PinnedInit` for your struct delegating to all fields marked
-/// with `#[init]`.
+///   with `#[init]`.
+/// - creates a `{your-struct-name}InitParams` struct with a member for the
+///   parameter of every field marked with `#[init]`, used as the parameter for
+///   `PinnedInit`. Its builder uses the default parameter of every member, that
+///   was not set.
+/// - supports `#[init(param = <expr>)]` and `#[init(with = <fn>)]` to compute
+///   the parameter of a field or to initialize it with a custom function.
+/// - supports `param = { <fields> }` (`param = ( <types> )` for tuple structs) as
+///   an argument, adding members to `{your-struct-name}InitParams` that the
+///   expressions of `#[init(...)]` can use.
 /// - implements `TransmuteInto<{your-struct-name}>`()
-/// `for`{your-struct-name}Uninit` and checks for layout equivalence between the
-/// two.
+///   `for`{your-struct-name}Uninit` and checks for layout equivalence between the
+///   two.
 /// - creates a custom type borrowing from your struct that is used as the
-/// `OngoingInit` type for the `BeginPinnedInit` trait.
+///   `OngoingInit` type for the `BeginPinnedInit` trait.
 /// - implements `BeginPinnedInit` for your struct.
+/// - creates a `{your-struct-name}Slots` struct with a slot for every field,
+///   used to write the fields of `{your-struct-name}Uninit` in place.
+/// - for enums, the `OngoingInit` type is an enum with the same variants and the
+///   `InitParams` struct has a member with the init params of every variant.
 ///
 /// Then you can safely, soundly and ergonomically initialize a value of such a
 /// struct behind an `OwnedUniquePtr<{your-struct-name}>`:
-/// TODO example
+/// see the documentation of `pinned_init` for an example.
 #[proc_macro_error]
 #[proc_macro_attribute]
 pub fn pinned_init(
     attr: proc_macro::TokenStream,
     item: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    let res = pinned_init_inner(attr.into(), parse_macro_input!(item as ItemStruct));
+    let res = match parse_macro_input!(item as Item) {
+        Item::Struct(item) => pinned_init_inner(attr.into(), item),
+        Item::Enum(item) => pinned_init_enum_inner(attr.into(), item),
+        item => abort!(item, "Expected a struct or an enum."),
+    };
     res.into()
 }
 
-/// Use this attribute on a struct with named fields to ensure safer
-/// pinned initialization of all the fields marked with `#[init]`.
+/// Use this attribute on a struct with named or unnamed fields or on an enum to
+/// ensure safer pinned initialization of all the fields marked with `#[init]`.
 ///
 /// This attribute does several things, it:
 /// - `#[pin_project]`s your struct, structually pinning all fields with `#[pin]`.
 /// - adds a constant type parameter of type bool with a default value of true.
-/// This constant type parameter indicates if your struct is in an initialized
-/// (and thus also pinned) state. A type alias `{your-struct-name}Uninit` is
-/// created to refer to the uninitialized variant more ergonomically, it should
-/// always be used instead of specifying the const parameter.
+///   This constant type parameter indicates if your struct is in an initialized
+///   (and thus also pinned) state. A type alias `{your-struct-name}Uninit` is
+///   created to refer to the uninitialized variant more ergonomically, it should
+///   always be used instead of specifying the const parameter.
 /// - propagates that const parameter to all fields marked with `#[init]`.
 /// - implements `TransmuteInto<{your-struct-name}>`
-/// `for`{your-struct-name}Uninit` and checks for layout equivalence between the
-/// two.
+///   `for`{your-struct-name}Uninit` and checks for layout equivalence between the
+///   two.
 /// - creates a custom type borrowing from your struct that is used as the
-/// `OngoingInit` type for the `BeginPinnedInit` trait.
+///   `OngoingInit` type for the `BeginPinnedInit` trait.
 /// - implements `BeginPinnedInit` for your struct.
+/// - creates a `{your-struct-name}Slots` struct with a slot for every field,
+///   used to write the fields of `{your-struct-name}Uninit` in place.
+/// - for enums, the `OngoingInit` type is an enum with the same variants.
 ///
 /// The only thing you need to implement is `PinnedInit`.
 ///
 /// Then you can safely, soundly and ergonomically initialize a value of such a
 /// struct behind an `OwnedUniquePtr<{your-struct-name}>`:
-/// TODO example
+/// see the documentation of `pinned_init` for an example.
 #[proc_macro_attribute]
 #[proc_macro_error]
 pub fn manual_init(
     attr: proc_macro::TokenStream,
     item: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    let input = parse_macro_input!(item as ItemStruct);
-    let res = manual_init_inner(attr.into(), input);
+    let res = match parse_macro_input!(item as Item) {
+        Item::Struct(item) => manual_init_inner(attr.into(), item),
+        Item::Enum(item) => manual_init_enum_inner(attr.into(), item),
+        item => abort!(item, "Expected a struct or an enum."),
+    };
     res.into()
 }
 
+#[proc_macro_derive(DefaultParam)]
+#[proc_macro_error]
+pub fn derive_default_param(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    derive_default_param_inner(input).into()
+}
+
 #[proc_macro_derive(BeginInit, attributes(ongoing_init, init))]
 #[proc_macro_error]
 pub fn derive_begin_init(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -99,82 +135,113 @@ fn pinned_init_inner(
         ident,
         generics,
         mut fields,
-        semi_token,
+        semi_token: _,
     }: ItemStruct,
 ) -> TokenStream {
-    // Only structs with named fields are supported.
+    // Only structs with named or unnamed fields are supported.
     // To provide a better debugging experience, we only emit an error and
     // correct the fields value.
-    if !matches!(fields, Fields::Named(_)) {
-        emit_error!(fields, "Expected named fields with '{{}}'");
-        fields = match fields {
-            Fields::Unit => Fields::Named(parse_quote! { {} }),
-            Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => Fields::Named(FieldsNamed {
-                named: unnamed
-                    .into_iter()
-                    .enumerate()
-                    .map(|(i, mut f)| {
-                        f.ident = Some(format_ident!("__field{}", i));
-                        f
-                    })
-                    .collect(),
-                brace_token: Default::default(),
-            }),
-            Fields::Named(_) => unreachable!(),
-        };
+    if matches!(fields, Fields::Unit) {
+        emit_error!(
+            ident,
+            "Expected named fields with '{{}}' or unnamed fields with '()'"
+        );
+        fields = Fields::Named(parse_quote! { {} });
     }
+    let (attr, extra_params) = split_param_arg(attr);
     let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
-    let (init_fields, (pinned_field_types, param_pos)): (Vec<_>, (Vec<_>, Vec<Member>)) = fields
-        .iter_mut()
-        .filter(|f| {
-            f.attrs
-                .iter()
-                .any(|a| matches!(a.style, AttrStyle::Outer) && a.path.is_ident("init"))
-        })
+    let ongoing_init_ident = format_ident!("{}OngoingInit", ident);
+    let members = fields
+        .iter()
         .enumerate()
-        .map(|(i, f)| {
-            f.attrs.push(parse_quote! { #[pin] });
-            let ty = f.ty.clone();
-            (
-                f.ident.as_ref().unwrap().clone(),
-                (
-                    ty,
-                    Member::Unnamed(Index {
-                        index: i.try_into().unwrap(),
-                        span: Span::call_site(),
-                    }),
-                ),
-            )
-        })
-        .unzip();
+        .map(|(i, f)| field_member(i, f))
+        .collect::<Vec<_>>();
+    let names = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| scope_ident(i, f))
+        .collect::<Vec<_>>();
+    let bindings = names.iter().map(hygienic_field_ident).collect::<Vec<_>>();
+    let mut available = vec![true; names.len()];
+    let mut inits = vec![];
+    let mut param_members = vec![];
+    let mut param_types = vec![];
+    for (i, f) in fields.iter_mut().enumerate() {
+        if has_outer_attr(f.attrs.iter(), "init") {
+            // tuple structs get a tuple struct with only the parameters of the
+            // `#[init]` fields
+            let param_member = field_member(param_types.len(), f);
+            let scope = field_scope(&names, &bindings, &available);
+            let (init, param_type, consumed) =
+                pinned_init_field(f, &bindings[i], &quote! { param.#param_member }, &scope);
+            inits.push(init);
+            available[i] = !consumed;
+            if let Some(param_type) = param_type {
+                param_members.push(param_member);
+                param_types.push(param_type);
+            }
+        }
+    }
+    // the members given via `param = ...` follow the parameters of the fields
+    let extra_params = match (&fields, extra_params) {
+        (_, None) => quote! {},
+        (Fields::Named(_), Some(Fields::Named(FieldsNamed { named, .. }))) => quote! { #named },
+        (Fields::Unnamed(_), Some(Fields::Unnamed(FieldsUnnamed { unnamed, .. }))) => {
+            quote! { #unnamed }
+        }
+        (Fields::Named(_), Some(extra_params)) => {
+            emit_error!(
+                extra_params,
+                "Expected `param = { <fields> }` for a struct with named fields."
+            );
+            quote! {}
+        }
+        (_, Some(extra_params)) => {
+            emit_error!(
+                extra_params,
+                "Expected `param = ( <types> )` for a tuple struct."
+            );
+            quote! {}
+        }
+    };
+    let param_fields = if matches!(fields, Fields::Named(_)) {
+        Fields::Named(parse_quote! { { #(pub #param_members: #param_types,)* #extra_params } })
+    } else {
+        Fields::Unnamed(parse_quote! { ( #(pub #param_types,)* #extra_params ) })
+    };
+    let (init_params, param_type) = init_params(&vis, &ident, &generics, param_fields);
     let attr_comma = if attr.is_empty() {
         quote! {}
     } else {
         quote! {,}
     };
     let uninit_ident = format_ident!("{}Uninit", ident);
+    let body = struct_body(&generics.where_clause, &fields);
     quote! {
         // delegate to manual_init
         #[::pinned_init::manual_init(pinned #attr_comma #attr)]
         #(#attrs)*
-        #vis #struct_token #ident #generics #fields #semi_token
+        #vis #struct_token #ident #generics #body
 
         impl #impl_generics ::pinned_init::PinnedInit for #uninit_ident #type_generics
             #where_clause
         {
             type Initialized = #ident #type_generics;
-            // TODO correct param stuff
-            type Param = (#(<<#pinned_field_types as ::pinned_init::private::AsUninit>::Uninit as ::pinned_init::PinnedInit>::Param),*,);
+            type Param = #param_type;
 
+            #[allow(unused_variables, unused_mut)]
             fn init_raw(this: ::pinned_init::needs_init::NeedsPinnedInit<Self>, param: Self::Param) {
-                // just begin our init process and call init_raw on each field
-                // marked with #[init]
-                let this = ::pinned_init::needs_init::NeedsPinnedInit::begin_init(this);
-                #(
-                    ::pinned_init::PinnedInit::init_raw(this.#init_fields, param.#param_pos);
-                )*
+                // just begin our init process and initialize each field marked
+                // with #[init], the expressions given via #[init(...)] can use
+                // `param` and the fields, the generated code only uses the
+                // hygienic bindings.
+                let #ongoing_init_ident { #(#members: mut #bindings,)* } =
+                    ::pinned_init::needs_init::NeedsPinnedInit::begin_init(this);
+                #(#inits)*
             }
         }
+
+        #init_params
     }
 }
 
@@ -190,121 +257,255 @@ fn manual_init_inner(
         semi_token: _,
     }: ItemStruct,
 ) -> TokenStream {
-    let my_attrs = match parse_attrs.parse2(attr) {
-        Ok(attrs) => attrs,
+    let (is_pinned, is_deinit, pin_project_attrs) = match manual_init_params(attr) {
+        Ok(params) => params,
         Err(e) => return e.to_compile_error(),
     };
-    let is_pinned = my_attrs
-        .iter()
-        .any(|p| matches!(p, ManualInitParam::Pinned));
-    let pin_project_attrs = my_attrs
-        .into_iter()
-        .filter_map(|p| {
-            if let ManualInitParam::PinProject(raw) = p {
-                Some(raw)
-            } else {
-                None
-            }
-        })
-        .reduce(|a, b| quote! { #a #b })
-        .map(|a| quote! { (#a) });
-    if !is_pinned && pin_project_attrs.is_some() {
-        emit_error!(
-            pin_project_attrs.as_ref().unwrap(),
-            "Pinned attribute not supplied, pin_project is not applied."
-        );
-    }
-    // Only structs with named fields are supported.
+    // Only structs with named or unnamed fields are supported.
     // To provide a better debugging experience, we only emit an error and
     // correct the fields value.
-    if !matches!(fields, Fields::Named(_)) {
-        emit_error!(fields, "Expected named fields with '{{}}'");
-        fields = match fields {
-            Fields::Unit => Fields::Named(parse_quote! { {} }),
-            Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => Fields::Named(FieldsNamed {
-                named: unnamed
-                    .into_iter()
-                    .enumerate()
-                    .map(|(i, mut f)| {
-                        f.ident = Some(format_ident!("__field{}", i));
-                        f
-                    })
-                    .collect(),
-                brace_token: Default::default(),
-            }),
-            Fields::Named(_) => unreachable!(),
-        };
+    if matches!(fields, Fields::Unit) {
+        emit_error!(
+            ident,
+            "Expected named fields with '{{}}' or unnamed fields with '()'"
+        );
+        fields = Fields::Named(parse_quote! { {} });
     }
     let uninit_ident = format_ident!("{}Uninit", ident);
     let ongoing_init_ident = format_ident!("{}OngoingInit", ident);
     // `'__ongoing_init` for the OngoingInit type
     let ongoing_init_lifetime = quote! { '__ongoing_init };
     // add a where clause that is empty or trailing
-    let where_clause = generics.make_where_clause();
-    if !where_clause.predicates.empty_or_trailing() {
-        where_clause.predicates.push_punct(Default::default());
-    }
-    // ensure that we have a `where`
-    let where_clause = if where_clause.predicates.is_empty() {
-        quote! {where}
-    } else {
-        quote! { #where_clause }
-    };
+    let where_clause = trailing_where_clause(&mut generics);
     let (impl_generics, type_generics, _) = my_split_for_impl(&generics);
     let comma = if impl_generics.is_empty() {
         quote! {}
     } else {
         quote! {,}
     };
+    forbid_init_args(&fields);
     let uninit_fields = make_uninit_fields(fields.clone());
     let ongoing_init_fields =
         make_ongoing_init_fields(uninit_fields.clone(), &ongoing_init_lifetime);
     let all_fields = fields
         .iter_mut()
-        .map(|f| {
+        .enumerate()
+        .map(|(i, f)| {
             f.attrs.retain(|a| {
                 !(matches!(a.style, AttrStyle::Outer)
                     && (a.path.is_ident("init") || a.path.is_ident("uninit")))
             });
-            f.ident.as_ref().unwrap().clone()
+            field_member(i, f)
         })
         .collect::<Vec<_>>();
-    let check_mod = quote! {
-        // define constants to ensure the layout between init and uninit is the
-        // same
-        impl <#impl_generics> #uninit_ident<#type_generics> {
-            const __CHECK_ALIGNMENT: () = {
-                if ::core::mem::align_of::<#uninit_ident<#type_generics>>() != ::core::mem::align_of::<#ident<#type_generics>>() {
-                    panic!(concat!("The alignments of the uninitialized and initialized variants of the type `", stringify!(#ident<#type_generics>), "` are not identical."));
+    // collect the information needed to track the initialization of the
+    // `#[init]` fields and to drop a partially initialized value.
+    let mut init_fields = vec![];
+    let mut init_members = vec![];
+    let mut init_field_types = vec![];
+    let mut drop_fields = vec![];
+    for (i, f) in uninit_fields.iter().enumerate() {
+        let field = field_member(i, f);
+        if has_outer_attr(f.attrs.iter(), "init") {
+            let ty = &f.ty;
+            let flag = field_ident(i, f);
+            drop_fields.push(quote! {
+                <#ty as ::pinned_init::private::PartialInit>::__drop_partial(::core::ptr::addr_of_mut!((*this).#field), &flags.#flag);
+            });
+            init_fields.push(flag);
+            init_members.push(field);
+            init_field_types.push(ty.clone());
+        } else {
+            drop_fields.push(quote! {
+                ::core::ptr::drop_in_place(::core::ptr::addr_of_mut!((*this).#field));
+            });
+        }
+    }
+    // fields are dropped in reverse order, because they are initialized in order
+    drop_fields.reverse();
+    let phantom_lifetimes = generics.lifetimes().map(|l| &l.lifetime);
+    let phantom_types = generics.type_params().map(|t| &t.ident);
+    let partial_init = quote! {
+        // track the initialization of all fields marked with #[init], so we can
+        // drop the partially initialized value, when the initialization fails.
+        // The flags are defined inside of a constant, so they do not leak the
+        // types of private fields.
+        const _: () = {
+            #[doc(hidden)]
+            pub struct __InitFlags<#impl_generics>
+            #where_clause
+            {
+                #(#init_fields: <#init_field_types as ::pinned_init::private::PartialInit>::Flags,)*
+                __phantom: ::core::marker::PhantomData<fn() -> (#(&#phantom_lifetimes (),)* #(*const #phantom_types,)*)>,
+            }
+
+            #[allow(unused_variables)]
+            unsafe impl<#impl_generics> ::pinned_init::private::PartialInit for #uninit_ident<#type_generics>
+            #where_clause
+            {
+                type Flags = __InitFlags<#type_generics>;
+
+                #[inline]
+                fn __new_flags() -> Self::Flags {
+                    __InitFlags {
+                        #(#init_fields: <#init_field_types as ::pinned_init::private::PartialInit>::__new_flags(),)*
+                        __phantom: ::core::marker::PhantomData,
+                    }
                 }
-            };
-            const __CHECK_SIZE: () = {
-                if ::core::mem::size_of::<#uninit_ident<#type_generics>>() != ::core::mem::size_of::<#ident<#type_generics>>() {
-                    panic!(concat!("The sizes of the uninitialized and initialized variants of the type `", stringify!(#ident<#type_generics>), "` are not identical."));
+
+                #[inline]
+                unsafe fn __is_init(this: *const Self, flags: &Self::Flags) -> bool {
+                    unsafe {
+                        true #(&& <#init_field_types as ::pinned_init::private::PartialInit>::__is_init(::core::ptr::addr_of!((*this).#init_members), &flags.#init_fields))*
+                    }
                 }
-            };
-            const __CHECK_OFFSETS: () = {
-                #(
+
+                #[inline]
+                unsafe fn __is_uninit(this: *const Self, flags: &Self::Flags) -> bool {
                     unsafe {
-                        // create a valid allocation of uninit, we cannot use null
-                        // here, because that would be undefined behaviour
-                        let uninit = ::core::mem::MaybeUninit::<#uninit_ident<#type_generics>>::uninit();
-                        let u = uninit.as_ptr();
-                        // reinterpret the pointer
-                        let i = u as *const #ident<#type_generics>;
-                        // get each offset using the `offset_from` function, because
-                        // this function takes a *const T pointer, we cast both to
-                        // *const u8
-                        let u_off = (::core::ptr::addr_of!((*u).#all_fields) as *const u8).offset_from(u as *const u8);
-                        let i_off = (::core::ptr::addr_of!((*i).#all_fields) as *const u8).offset_from(i as *const u8);
-                        if u_off != i_off {
-                            panic!(concat!("The offset of `", stringify!(#all_fields), "` is not the same between the uninitialized and initialized variants of the type `", stringify!(#ident<#type_generics>), "`."));
+                        true #(&& <#init_field_types as ::pinned_init::private::PartialInit>::__is_uninit(::core::ptr::addr_of!((*this).#init_members), &flags.#init_fields))*
+                    }
+                }
+
+                #[inline]
+                fn __set_init(flags: &Self::Flags) {
+                    #(<#init_field_types as ::pinned_init::private::PartialInit>::__set_init(&flags.#init_fields);)*
+                }
+
+                unsafe fn __drop_partial(this: *mut Self, flags: &Self::Flags) {
+                    unsafe {
+                        if Self::__is_init(this, flags) {
+                            // all fields are initialized, so we drop the initialized variant.
+                            ::core::ptr::drop_in_place(this as *mut #ident<#type_generics>);
+                        } else if Self::__is_uninit(this, flags) {
+                            ::core::ptr::drop_in_place(this);
+                        } else {
+                            #(#drop_fields)*
                         }
-                    };
-                )*
-            };
+                    }
+                }
+            }
+        };
+    };
+    // generate a slot for every field, used to write the fields of the
+    // uninitialized value in place.
+    let slots_ident = format_ident!("{}Slots", ident);
+    let slot_fields = map_fields(uninit_fields.clone(), |mut f| {
+        let ty = f.ty;
+        f.ty = parse_quote! { ::pinned_init::slot::Slot<'__slots, #ty> };
+        f.attrs.clear();
+        f
+    });
+    let slot_idx = (0..all_fields.len())
+        .map(Literal::usize_unsuffixed)
+        .collect::<Vec<_>>();
+    let slot_flags = slot_idx
+        .iter()
+        .map(|_| quote! { ::core::cell::Cell::new(false) });
+    let slot_count = Literal::usize_unsuffixed(all_fields.len());
+    let rev_slot_idx = slot_idx.iter().rev();
+    let rev_fields = all_fields.iter().rev();
+    let write_uninit = quote! {
+        // track the fields that have been written, so we can drop them when
+        // writing fails.
+        #[allow(unused_variables)]
+        unsafe impl<#impl_generics> ::pinned_init::private::WriteUninit for #uninit_ident<#type_generics>
+        #where_clause
+        {
+            type Slots<'__slots> = #slots_ident<'__slots #comma #type_generics> where Self: '__slots;
+            type WriteFlags = [::core::cell::Cell<bool>; #slot_count];
+
+            #[inline]
+            fn __new_write_flags() -> Self::WriteFlags {
+                [#(#slot_flags),*]
+            }
+
+            #[inline]
+            unsafe fn __slots<'__slots>(this: *mut Self, flags: &'__slots Self::WriteFlags) -> Self::Slots<'__slots> {
+                unsafe {
+                    #slots_ident {
+                        #(#all_fields: ::pinned_init::slot::Slot::new_unchecked(::core::ptr::addr_of_mut!((*this).#all_fields), &flags[#slot_idx]),)*
+                    }
+                }
+            }
+
+            #[inline]
+            fn __is_written(flags: &Self::WriteFlags) -> bool {
+                flags.iter().all(::core::cell::Cell::get)
+            }
+
+            unsafe fn __drop_written(this: *mut Self, flags: &Self::WriteFlags) {
+                unsafe {
+                    #(
+                        if flags[#rev_slot_idx].get() {
+                            ::core::ptr::drop_in_place(::core::ptr::addr_of_mut!((*this).#rev_fields));
+                        }
+                    )*
+                }
+            }
         }
     };
+    let check_mod = check_layout(
+        &ident,
+        &uninit_ident,
+        &impl_generics,
+        &type_generics,
+        &where_clause,
+        quote! {
+            #(
+                unsafe {
+                    // create a valid allocation of uninit, we cannot use null
+                    // here, because that would be undefined behaviour
+                    let uninit = ::core::mem::MaybeUninit::<#uninit_ident<#type_generics>>::uninit();
+                    let u = uninit.as_ptr();
+                    // reinterpret the pointer
+                    let i = u as *const #ident<#type_generics>;
+                    // get each offset using the `offset_from` function, because
+                    // this function takes a *const T pointer, we cast both to
+                    // *const u8
+                    let u_off = (::core::ptr::addr_of!((*u).#all_fields) as *const u8).offset_from(u as *const u8);
+                    let i_off = (::core::ptr::addr_of!((*i).#all_fields) as *const u8).offset_from(i as *const u8);
+                    if u_off != i_off {
+                        panic!(concat!("The offset of `", stringify!(#all_fields), "` is not the same between the uninitialized and initialized variants of the type `", stringify!(#ident<#type_generics>), "`."));
+                    }
+                };
+            )*
+        },
+    );
+    let uninit_impls = uninit_impls(
+        &ident,
+        &uninit_ident,
+        &impl_generics,
+        &type_generics,
+        &where_clause,
+    );
+    let deinit = if is_deinit && is_pinned {
+        let deinit_types = fields
+            .iter()
+            .zip(uninit_fields.iter())
+            .filter(|(_, u)| has_outer_attr(u.attrs.iter(), "init"))
+            .map(|(f, _)| &f.ty)
+            .collect::<Vec<_>>();
+        pinned_deinit(
+            &ident,
+            &uninit_ident,
+            &impl_generics,
+            &type_generics,
+            &where_clause,
+            &init_members,
+            &init_field_types,
+            &deinit_types,
+        )
+    } else {
+        quote! {}
+    };
+    let body = struct_body(&where_clause, &fields);
+    let uninit_body = struct_body(&where_clause, &uninit_fields);
+    let slots_body = struct_body(quote! { #where_clause Self: '__slots, }, &slot_fields);
+    let ongoing_init_body = struct_body(
+        quote! { #where_clause Self: #ongoing_init_lifetime, },
+        &ongoing_init_fields,
+    );
     let pin_project = if is_pinned {
         quote! { #[::pinned_init::__private::pin_project #pin_project_attrs] }
     } else {
@@ -315,32 +516,710 @@ fn manual_init_inner(
     } else {
         quote! {#[derive(::pinned_init::private::BeginInit)]}
     };
-    if is_pinned {
-    } else {
-    }
     quote! {
         #[repr(C)]
         #pin_project
         #(#attrs)*
-        #vis #struct_token #ident <#impl_generics> #where_clause #fields
+        #vis #struct_token #ident <#impl_generics> #body
 
         #[repr(C)]
         #begin_init
         #pin_project
         #[ongoing_init(#ongoing_init_ident)]
         #(#attrs)*
-        #vis #struct_token #uninit_ident <#impl_generics> #where_clause #uninit_fields
+        #vis #struct_token #uninit_ident <#impl_generics> #uninit_body
 
         #check_mod
 
+        #partial_init
+
+        // define a new struct used to write the fields of the uninitialized
+        // value in place.
+        #[allow(dead_code)]
+        #vis #struct_token #slots_ident <'__slots #comma #impl_generics> #slots_body
+
+        #write_uninit
+
         // define a new struct used to handle the ongoing initialization.
         // allow dead_code, because some fields may not be used in initialization.
         #[allow(dead_code)]
-        #vis #struct_token #ongoing_init_ident <#ongoing_init_lifetime #comma #impl_generics>
+        #vis #struct_token #ongoing_init_ident <#ongoing_init_lifetime #comma #impl_generics> #ongoing_init_body
+
+        #uninit_impls
+
+        #deinit
+    }
+}
+
+fn pinned_init_enum_inner(
+    attr: TokenStream,
+    ItemEnum {
+        attrs,
+        vis,
+        enum_token,
+        ident,
+        generics,
+        brace_token: _,
+        mut variants,
+    }: ItemEnum,
+) -> TokenStream {
+    let (attr, extra_params) = split_param_arg(attr);
+    if let Some(extra_params) = extra_params {
+        emit_error!(
+            extra_params,
+            "`param` is not supported on enums, `param` refers to the init params of the variant."
+        );
+    }
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let ongoing_init_ident = format_ident!("{}OngoingInit", ident);
+    // every variant gets its own init params with only the parameters of its
+    // `#[init]` fields, the init params of the enum have a member with the
+    // init params of every variant.
+    let mut variant_params = vec![];
+    let mut variant_param_members = vec![];
+    let mut variant_param_types = vec![];
+    let mut init_arms = vec![];
+    for variant in variants.iter_mut() {
+        let variant_ident = &variant.ident;
+        let variant_param_member = variant_params_ident(variant_ident);
+        let members = variant
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| field_member(i, f))
+            .collect::<Vec<_>>();
+        let names = variant
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| scope_ident(i, f))
+            .collect::<Vec<_>>();
+        let bindings = names.iter().map(hygienic_field_ident).collect::<Vec<_>>();
+        let mut available = vec![true; names.len()];
+        let mut inits = vec![];
+        let mut param_members = vec![];
+        let mut param_types = vec![];
+        for (i, f) in variant.fields.iter_mut().enumerate() {
+            if has_outer_attr(f.attrs.iter(), "init") {
+                let param_member = field_member(param_types.len(), f);
+                let scope = field_scope(&names, &bindings, &available);
+                let (init, param_type, consumed) =
+                    pinned_init_field(f, &bindings[i], &quote! { param.#param_member }, &scope);
+                inits.push(init);
+                available[i] = !consumed;
+                if let Some(param_type) = param_type {
+                    param_members.push(param_member);
+                    param_types.push(param_type);
+                }
+            }
+        }
+        let param_fields = match &variant.fields {
+            Fields::Named(_) => {
+                Fields::Named(parse_quote! { { #(pub #param_members: #param_types,)* } })
+            }
+            Fields::Unnamed(_) => Fields::Unnamed(parse_quote! { ( #(pub #param_types,)* ) }),
+            Fields::Unit => Fields::Unit,
+        };
+        let (definitions, param_type) = init_params(
+            &vis,
+            &format_ident!("{}{}", ident, variant_ident.unraw()),
+            &generics,
+            param_fields,
+        );
+        variant_params.push(definitions);
+        init_arms.push(quote! {
+            #ongoing_init_ident::#variant_ident { #(#members: mut #bindings,)* } => {
+                // only the init params of the current variant are used
+                let param = param.#variant_param_member;
+                #(#inits)*
+            }
+        });
+        variant_param_members.push(variant_param_member);
+        variant_param_types.push(param_type);
+    }
+    let (init_params, param_type) = init_params(
+        &vis,
+        &ident,
+        &generics,
+        Fields::Named(parse_quote! { { #(pub #variant_param_members: #variant_param_types,)* } }),
+    );
+    let attr_comma = if attr.is_empty() {
+        quote! {}
+    } else {
+        quote! {,}
+    };
+    let uninit_ident = format_ident!("{}Uninit", ident);
+    quote! {
+        // delegate to manual_init
+        #[::pinned_init::manual_init(pinned #attr_comma #attr)]
+        #(#attrs)*
+        #vis #enum_token #ident #generics #where_clause {
+            #variants
+        }
+
+        impl #impl_generics ::pinned_init::PinnedInit for #uninit_ident #type_generics
+            #where_clause
+        {
+            type Initialized = #ident #type_generics;
+            type Param = #param_type;
+
+            #[allow(unused_variables, unused_mut)]
+            fn init_raw(this: ::pinned_init::needs_init::NeedsPinnedInit<Self>, param: Self::Param) {
+                // just begin our init process and initialize each field of the
+                // current variant marked with #[init], using the init params
+                // of that variant.
+                match ::pinned_init::needs_init::NeedsPinnedInit::begin_init(this) {
+                    #(#init_arms)*
+                }
+            }
+        }
+
+        #(#variant_params)*
+
+        #init_params
+    }
+}
+
+fn manual_init_enum_inner(
+    attr: TokenStream,
+    ItemEnum {
+        attrs,
+        vis,
+        enum_token,
+        ident,
+        mut generics,
+        brace_token: _,
+        mut variants,
+    }: ItemEnum,
+) -> TokenStream {
+    let (is_pinned, is_deinit, pin_project_attrs) = match manual_init_params(attr) {
+        Ok(params) => params,
+        Err(e) => return e.to_compile_error(),
+    };
+    if is_deinit {
+        emit_call_site_error!("deinit is not supported on enums.");
+    }
+    // `#[repr(C)]` is not allowed on enums without variants.
+    if variants.is_empty() {
+        abort!(ident, "Expected at least one variant.");
+    }
+    let uninit_ident = format_ident!("{}Uninit", ident);
+    let ongoing_init_ident = format_ident!("{}OngoingInit", ident);
+    // `'__ongoing_init` for the OngoingInit type
+    let ongoing_init_lifetime = quote! { '__ongoing_init };
+    // add a where clause that is empty or trailing
+    let where_clause = trailing_where_clause(&mut generics);
+    let (impl_generics, type_generics, _) = my_split_for_impl(&generics);
+    let comma = if impl_generics.is_empty() {
+        quote! {}
+    } else {
+        quote! {,}
+    };
+    forbid_init_args(variants.iter().flat_map(|v| &v.fields));
+    let uninit_variants = variants
+        .iter()
+        .cloned()
+        .map(|mut v| {
+            v.fields = make_uninit_fields(v.fields);
+            v
+        })
+        .collect::<Vec<_>>();
+    let ongoing_init_variants = uninit_variants
+        .iter()
+        .cloned()
+        .map(|mut v| {
+            v.attrs.clear();
+            v.discriminant = None;
+            v.fields = make_ongoing_init_fields(v.fields, &ongoing_init_lifetime);
+            v
+        })
+        .collect::<Vec<_>>();
+    // collect the information needed to track the initialization of the
+    // `#[init]` fields of every variant, to drop a partially initialized
+    // value and to check the layout of the fields.
+    let mut init_flags = vec![];
+    let mut init_flag_types = vec![];
+    let mut is_init_arms = vec![];
+    let mut is_uninit_arms = vec![];
+    let mut drop_arms = vec![];
+    let mut layout_checks = vec![];
+    for (variant, uninit_variant) in variants.iter_mut().zip(&uninit_variants) {
+        let variant_ident = &variant.ident;
+        let mut members = vec![];
+        let mut bindings = vec![];
+        let mut init_members = vec![];
+        let mut init_bindings = vec![];
+        let mut init_types = vec![];
+        let mut flags = vec![];
+        let mut drop_fields = vec![];
+        for (i, (f, uninit_f)) in variant
+            .fields
+            .iter_mut()
+            .zip(&uninit_variant.fields)
+            .enumerate()
+        {
+            let member = field_member(i, f);
+            let binding = binding_ident(i);
+            if has_outer_attr(uninit_f.attrs.iter(), "init") {
+                let ty = &f.ty;
+                let uninit_ty = &uninit_f.ty;
+                let flag = variant_field_ident(variant_ident, i, f);
+                drop_fields.push(quote! {
+                    <#uninit_ty as ::pinned_init::private::PartialInit>::__drop_partial(#binding, &flags.#flag);
+                });
+                // both enums are `#[repr(C)]`, so they have the same layout,
+                // if all fields have the same size and alignment.
+                layout_checks.push(quote! {
+                    if ::core::mem::size_of::<#uninit_ty>() != ::core::mem::size_of::<#ty>()
+                        || ::core::mem::align_of::<#uninit_ty>() != ::core::mem::align_of::<#ty>()
+                    {
+                        panic!(concat!("The layout of `", stringify!(#variant_ident::#member), "` is not the same between the uninitialized and initialized variants of the type `", stringify!(#ident<#type_generics>), "`."));
+                    }
+                });
+                init_members.push(member.clone());
+                init_bindings.push(binding.clone());
+                init_types.push(uninit_ty.clone());
+                init_flag_types.push(uninit_ty.clone());
+                init_flags.push(flag.clone());
+                flags.push(flag);
+            } else {
+                drop_fields.push(quote! {
+                    ::core::ptr::drop_in_place(#binding);
+                });
+            }
+            f.attrs.retain(|a| {
+                !(matches!(a.style, AttrStyle::Outer)
+                    && (a.path.is_ident("init") || a.path.is_ident("uninit")))
+            });
+            members.push(member);
+            bindings.push(binding);
+        }
+        // fields are dropped in reverse order, because they are initialized in order
+        drop_fields.reverse();
+        is_init_arms.push(quote! {
+            Self::#variant_ident { #(#init_members: #init_bindings,)* .. } => {
+                true #(&& <#init_types as ::pinned_init::private::PartialInit>::__is_init(#init_bindings, &flags.#flags))*
+            }
+        });
+        is_uninit_arms.push(quote! {
+            Self::#variant_ident { #(#init_members: #init_bindings,)* .. } => {
+                true #(&& <#init_types as ::pinned_init::private::PartialInit>::__is_uninit(#init_bindings, &flags.#flags))*
+            }
+        });
+        drop_arms.push(quote! {
+            Self::#variant_ident { #(#members: #bindings,)* } => {
+                #(#drop_fields)*
+            }
+        });
+    }
+    let phantom_lifetimes = generics.lifetimes().map(|l| &l.lifetime);
+    let phantom_types = generics.type_params().map(|t| &t.ident);
+    let partial_init = quote! {
+        // track the initialization of all fields marked with #[init], so we can
+        // drop the partially initialized value, when the initialization fails.
+        // Every variant has its own flags, only the flags of the current
+        // variant are used.
+        const _: () = {
+            #[doc(hidden)]
+            #[allow(non_snake_case)]
+            pub struct __InitFlags<#impl_generics>
+            #where_clause
+            {
+                #(#init_flags: <#init_flag_types as ::pinned_init::private::PartialInit>::Flags,)*
+                __phantom: ::core::marker::PhantomData<fn() -> (#(&#phantom_lifetimes (),)* #(*const #phantom_types,)*)>,
+            }
+
+            #[allow(unused_variables)]
+            unsafe impl<#impl_generics> ::pinned_init::private::PartialInit for #uninit_ident<#type_generics>
+            #where_clause
+            {
+                type Flags = __InitFlags<#type_generics>;
+
+                #[inline]
+                fn __new_flags() -> Self::Flags {
+                    __InitFlags {
+                        #(#init_flags: <#init_flag_types as ::pinned_init::private::PartialInit>::__new_flags(),)*
+                        __phantom: ::core::marker::PhantomData,
+                    }
+                }
+
+                #[inline]
+                unsafe fn __is_init(this: *const Self, flags: &Self::Flags) -> bool {
+                    unsafe {
+                        // the discriminant is never changed during the
+                        // initialization and every field is valid in its
+                        // uninitialized form.
+                        match &*this {
+                            #(#is_init_arms)*
+                        }
+                    }
+                }
+
+                #[inline]
+                unsafe fn __is_uninit(this: *const Self, flags: &Self::Flags) -> bool {
+                    unsafe {
+                        match &*this {
+                            #(#is_uninit_arms)*
+                        }
+                    }
+                }
+
+                #[inline]
+                fn __set_init(flags: &Self::Flags) {
+                    #(<#init_flag_types as ::pinned_init::private::PartialInit>::__set_init(&flags.#init_flags);)*
+                }
+
+                unsafe fn __drop_partial(this: *mut Self, flags: &Self::Flags) {
+                    unsafe {
+                        if Self::__is_init(this, flags) {
+                            // all fields are initialized, so we drop the initialized variant.
+                            ::core::ptr::drop_in_place(this as *mut #ident<#type_generics>);
+                        } else if Self::__is_uninit(this, flags) {
+                            ::core::ptr::drop_in_place(this);
+                        } else {
+                            match &mut *this {
+                                #(#drop_arms)*
+                            }
+                        }
+                    }
+                }
+            }
+        };
+    };
+    let check_mod = check_layout(
+        &ident,
+        &uninit_ident,
+        &impl_generics,
+        &type_generics,
+        &where_clause,
+        quote! { #(#layout_checks)* },
+    );
+    let uninit_impls = uninit_impls(
+        &ident,
+        &uninit_ident,
+        &impl_generics,
+        &type_generics,
+        &where_clause,
+    );
+    // the arguments for `#[pin_project]` only apply to the initialized enum,
+    // the uninitialized enum needs a projection with a known name.
+    let (pin_project, uninit_pin_project) = if is_pinned {
+        let projection_ident = projection_ident(&uninit_ident);
+        (
+            quote! { #[::pinned_init::__private::pin_project #pin_project_attrs] },
+            quote! { #[::pinned_init::__private::pin_project(project = #projection_ident)] },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+    let begin_init = if is_pinned {
+        quote! {#[derive(::pinned_init::private::BeginPinnedInit)]}
+    } else {
+        quote! {#[derive(::pinned_init::private::BeginInit)]}
+    };
+    quote! {
+        #[repr(C)]
+        #pin_project
+        #(#attrs)*
+        #vis #enum_token #ident <#impl_generics> #where_clause {
+            #variants
+        }
+
+        #[repr(C)]
+        #begin_init
+        #uninit_pin_project
+        #[ongoing_init(#ongoing_init_ident)]
+        #(#attrs)*
+        #vis #enum_token #uninit_ident <#impl_generics> #where_clause {
+            #(#uninit_variants,)*
+        }
+
+        #check_mod
+
+        #partial_init
+
+        // define a new enum used to handle the ongoing initialization of the
+        // current variant.
+        // allow dead_code, because some fields may not be used in initialization.
+        #[allow(dead_code)]
+        #vis #enum_token #ongoing_init_ident <#ongoing_init_lifetime #comma #impl_generics>
         #where_clause
             Self: #ongoing_init_lifetime,
-        #ongoing_init_fields
+        {
+            #(#ongoing_init_variants,)*
+        }
+
+        #uninit_impls
+    }
+}
+
+/// prepares a field of a `#[pinned_init]` type marked with `#[init]` for
+/// `#[manual_init]` and returns the statement initializing the field bound to
+/// `binding`. When the parameter of the field is taken from the init params, it
+/// is accessed via `from_params` and its type is returned as well. The
+/// expressions given via `#[init(...)]` are evaluated in `scope`, the returned
+/// bool indicates if `binding` was consumed by `with`.
+fn pinned_init_field(
+    field: &mut Field,
+    binding: &Ident,
+    from_params: &TokenStream,
+    scope: &TokenStream,
+) -> (TokenStream, Option<TokenStream>, bool) {
+    let InitArgs {
+        param,
+        with,
+        unpinned,
+    } = parse_init_args(&field.attrs);
+    // manual_init does not support the arguments of #[init]
+    field
+        .attrs
+        .retain(|a| !(matches!(a.style, AttrStyle::Outer) && a.path.is_ident("init")));
+    field.attrs.push(parse_quote! { #[init] });
+    // unpinned fields get a `NeedsInit` and are initialized via `Init`
+    let (init_trait, needs_init) = if unpinned {
+        (
+            quote! { ::pinned_init::Init },
+            quote! { ::pinned_init::needs_init::NeedsInit },
+        )
+    } else {
+        field.attrs.push(parse_quote! { #[pin] });
+        (
+            quote! { ::pinned_init::PinnedInit },
+            quote! { ::pinned_init::needs_init::NeedsPinnedInit },
+        )
+    };
+    let ty = &field.ty;
+    let with_ident = Ident::new("__with", Span::mixed_site());
+    let param_ident = Ident::new("__param", Span::mixed_site());
+    // after its initialization, the binding of a field refers to the
+    // initialized value, so the following fields can depend on it.
+    match (with, param) {
+        (Some(with), Some(param)) => (
+            quote! {
+                let #with_ident = { #scope #with };
+                let #param_ident = { #scope #param };
+                (#with_ident)(#binding, #param_ident);
+            },
+            None,
+            true,
+        ),
+        (Some(with), None) => (
+            quote! {
+                let #with_ident = { #scope #with };
+                (#with_ident)(#binding);
+            },
+            None,
+            true,
+        ),
+        (None, Some(param)) => (
+            quote! {
+                let #param_ident = { #scope #param };
+                let mut #binding = #needs_init::init_with(#binding, #param_ident);
+            },
+            None,
+            false,
+        ),
+        (None, None) => (
+            quote! { let mut #binding = #needs_init::init_with(#binding, #from_params); },
+            Some(quote! {
+                <<#ty as ::pinned_init::private::AsUninit>::Uninit as #init_trait>::Param
+            }),
+            false,
+        ),
+    }
+}
+
+/// binds the names of the fields that were not consumed by `with` to mutable
+/// references to their hygienic bindings, so the expressions given via
+/// `#[init(...)]` can refer to them.
+fn field_scope(names: &[Ident], bindings: &[Ident], available: &[bool]) -> TokenStream {
+    let (names, bindings): (Vec<_>, Vec<_>) = names
+        .iter()
+        .zip(bindings)
+        .zip(available)
+        .filter(|(_, available)| **available)
+        .map(|(pair, _)| pair)
+        .unzip();
+    quote! { #(let #names = &mut #bindings;)* }
+}
+
+/// generates the `{ident}InitParams` struct with a type parameter for every
+/// member of the given fields, derives `Default` and `DefaultParam` for it and
+/// adds a builder, that uses the `DefaultParam` of the members that were not
+/// set. Returns the definitions and the type to use as `PinnedInit::Param`.
+fn init_params(
+    vis: &Visibility,
+    ident: &Ident,
+    generics: &Generics,
+    fields: Fields,
+) -> (TokenStream, TokenStream) {
+    let params_ident = format_ident!("{}InitParams", ident);
+    let members = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| field_member(i, f))
+        .collect::<Vec<_>>();
+    let types = fields.iter().map(|f| f.ty.clone()).collect::<Vec<_>>();
+    let generics_idents = (0..members.len())
+        .map(|i| format_ident!("__P{}", i))
+        .collect::<Vec<_>>();
+    let built = (0..members.len())
+        .map(|i| format_ident!("__Q{}", i))
+        .collect::<Vec<_>>();
+    // the type parameters default to the parameters, unless they depend on
+    // the generics of the struct, so the init params can still be named
+    // without any generics.
+    let defaults = if filter_generics(generics, quote! { #(#types)* })
+        .params
+        .is_empty()
+    {
+        types.iter().map(|ty| quote! { = #ty }).collect::<Vec<_>>()
+    } else {
+        types.iter().map(|_| quote! {}).collect()
+    };
+    let mut generic_types = generics_idents.iter();
+    let fields = map_fields(fields, |mut f| {
+        let ty = generic_types.next().unwrap();
+        f.ty = parse_quote! { #ty };
+        f
+    });
+    let body = struct_body(quote! {}, &fields);
+    let unset = members.iter().map(|_| quote! { ::pinned_init::Unset });
+    let setters = members.iter().enumerate().map(|(i, member)| {
+        let setter = match member {
+            Member::Named(ident) => format_ident!("with_{}", ident.unraw()),
+            Member::Unnamed(index) => format_ident!("with_{}", index.index),
+        };
+        let others = members.iter().filter(|m| *m != member).collect::<Vec<_>>();
+        let mut changed = generics_idents
+            .iter()
+            .map(|g| quote! { #g })
+            .collect::<Vec<_>>();
+        changed[i] = quote! { __V };
+        quote! {
+            #[inline]
+            pub fn #setter<__V>(self, value: __V) -> #params_ident<#(#changed),*> {
+                #params_ident {
+                    #member: value,
+                    #(#others: self.#others,)*
+                }
+            }
+        }
+    });
+    let definitions = quote! {
+        // `DefaultParam` allows `SafePinnedInit::init`, when all parameters
+        // have a default.
+        #[derive(::core::default::Default, ::pinned_init::DefaultParam)]
+        #vis struct #params_ident<#(#generics_idents #defaults),*> #body
+
+        impl #params_ident<#(#unset),*> {
+            /// Creates the init params with every member unset, members that
+            /// are still unset when [`Self::build`] is called use their
+            /// default parameter.
+            #[inline]
+            pub fn builder() -> Self {
+                #params_ident {
+                    #(#members: ::pinned_init::Unset,)*
+                }
+            }
+        }
+
+        impl<#(#generics_idents),*> #params_ident<#(#generics_idents),*> {
+            #(#setters)*
+
+            /// Replaces every unset member with its default parameter.
+            #[inline]
+            pub fn build<#(#built),*>(self) -> #params_ident<#(#built),*>
+            where
+                #(#generics_idents: ::pinned_init::private::BuildParam<#built>,)*
+            {
+                #params_ident {
+                    #(#members: ::pinned_init::private::BuildParam::__build_param(self.#members),)*
+                }
+            }
+        }
+    };
+    (definitions, quote! { #params_ident<#(#types),*> })
+}
 
+/// parses the arguments of `#[manual_init]`, returns if `pinned` and `deinit`
+/// were specified and the arguments for `#[pin_project]`.
+fn manual_init_params(attr: TokenStream) -> Result<(bool, bool, Option<TokenStream>)> {
+    let my_attrs = parse_attrs.parse2(attr)?;
+    let is_pinned = my_attrs
+        .iter()
+        .any(|p| matches!(p, ManualInitParam::Pinned));
+    let is_deinit = my_attrs
+        .iter()
+        .any(|p| matches!(p, ManualInitParam::Deinit));
+    let pin_project_attrs = my_attrs
+        .into_iter()
+        .filter_map(|p| {
+            if let ManualInitParam::PinProject(raw) = p {
+                Some(raw)
+            } else {
+                None
+            }
+        })
+        .reduce(|a, b| quote! { #a #b })
+        .map(|a| quote! { (#a) });
+    if !is_pinned && pin_project_attrs.is_some() {
+        emit_error!(
+            pin_project_attrs.as_ref().unwrap(),
+            "Pinned attribute not supplied, pin_project is not applied."
+        );
+    }
+    if is_deinit && !is_pinned {
+        emit_call_site_error!("Pinned attribute not supplied, deinit is not supported.");
+    }
+    if is_deinit && has_pinned_drop(&pin_project_attrs) {
+        emit_error!(
+            pin_project_attrs.as_ref().unwrap(),
+            "deinit cannot be used together with PinnedDrop, because it would not run."
+        );
+    }
+    Ok((is_pinned, is_deinit, pin_project_attrs))
+}
+
+/// defines constants to ensure the layout between init and uninit is the
+/// same, `offsets` checks the layout of the individual fields.
+fn check_layout(
+    ident: &Ident,
+    uninit_ident: &Ident,
+    impl_generics: &TokenStream,
+    type_generics: &TokenStream,
+    where_clause: &TokenStream,
+    offsets: TokenStream,
+) -> TokenStream {
+    quote! {
+        impl <#impl_generics> #uninit_ident<#type_generics> #where_clause {
+            const __CHECK_ALIGNMENT: () = {
+                if ::core::mem::align_of::<#uninit_ident<#type_generics>>() != ::core::mem::align_of::<#ident<#type_generics>>() {
+                    panic!(concat!("The alignments of the uninitialized and initialized variants of the type `", stringify!(#ident<#type_generics>), "` are not identical."));
+                }
+            };
+            const __CHECK_SIZE: () = {
+                if ::core::mem::size_of::<#uninit_ident<#type_generics>>() != ::core::mem::size_of::<#ident<#type_generics>>() {
+                    panic!(concat!("The sizes of the uninitialized and initialized variants of the type `", stringify!(#ident<#type_generics>), "` are not identical."));
+                }
+            };
+            const __CHECK_OFFSETS: () = {
+                #offsets
+            };
+        }
+    }
+}
+
+/// implements `TransmuteInto<{ident}>` for `{uninit_ident}` and `AsUninit` for
+/// `{ident}`.
+fn uninit_impls(
+    ident: &Ident,
+    uninit_ident: &Ident,
+    impl_generics: &TokenStream,
+    type_generics: &TokenStream,
+    where_clause: &TokenStream,
+) -> TokenStream {
+    quote! {
         // implement TransmuteInto because we implement #[repr(C)] and all field types are either the same,
         // or TransmuteInto with their uninit variants.
         unsafe impl<#impl_generics> ::pinned_init::transmute::TransmuteInto<#ident<#type_generics>> for #uninit_ident<#type_generics>
@@ -361,6 +1240,105 @@ fn manual_init_inner(
     }
 }
 
+/// returns `true`, if the `pin_project` attributes contain `PinnedDrop`.
+fn has_pinned_drop(pin_project_attrs: &Option<TokenStream>) -> bool {
+    fn contains(tokens: TokenStream) -> bool {
+        tokens.into_iter().any(|t| match t {
+            TokenTree::Ident(i) => i == "PinnedDrop",
+            TokenTree::Group(g) => contains(g.stream()),
+            _ => false,
+        })
+    }
+    pin_project_attrs.clone().is_some_and(contains)
+}
+
+/// implements `PinnedDeinit<{ident}>` for `{uninit_ident}` and
+/// `TransmuteInto<{uninit_ident}>` for `{ident}`, deinitializing every field
+/// marked with `#[init]`.
+#[allow(clippy::too_many_arguments)]
+fn pinned_deinit(
+    ident: &Ident,
+    uninit_ident: &Ident,
+    impl_generics: &TokenStream,
+    type_generics: &TokenStream,
+    where_clause: &TokenStream,
+    init_members: &[Member],
+    uninit_types: &[Type],
+    init_types: &[&Type],
+) -> TokenStream {
+    quote! {
+        // the layout checks compare both variants, so they also cover this
+        // direction. Every value of the initialized variant is deinitialized in
+        // place before it is used as the uninitialized variant.
+        unsafe impl<#impl_generics> ::pinned_init::transmute::TransmuteInto<#uninit_ident<#type_generics>> for #ident<#type_generics>
+        #where_clause
+        {
+            unsafe fn transmute_ptr(this: *const Self) ->
+                *const #uninit_ident<#type_generics>
+            {
+                <#uninit_ident<#type_generics>>::__CHECK_ALIGNMENT;
+                <#uninit_ident<#type_generics>>::__CHECK_SIZE;
+                <#uninit_ident<#type_generics>>::__CHECK_OFFSETS;
+                unsafe {
+                    ::core::mem::transmute(this)
+                }
+            }
+        }
+
+        unsafe impl<#impl_generics> ::pinned_init::PinnedDeinit<#ident<#type_generics>> for #uninit_ident<#type_generics>
+        #where_clause
+            #(#uninit_types: ::pinned_init::PinnedDeinit<#init_types>,)*
+        {
+            #[allow(unused_variables)]
+            unsafe fn deinit_raw(this: *mut #ident<#type_generics>) {
+                Self::__CHECK_ALIGNMENT;
+                Self::__CHECK_SIZE;
+                Self::__CHECK_OFFSETS;
+                unsafe {
+                    #(<#uninit_types as ::pinned_init::PinnedDeinit<#init_types>>::deinit_raw(::core::ptr::addr_of_mut!((*this).#init_members));)*
+                }
+            }
+        }
+    }
+}
+
+fn derive_default_param_inner(
+    DeriveInput {
+        attrs: _,
+        vis: _,
+        ident,
+        mut generics,
+        data,
+    }: DeriveInput,
+) -> TokenStream {
+    let fields = match data {
+        Data::Struct(s) => s.fields,
+        _ => abort!(ident, "Can only derive DefaultParam for structs."),
+    };
+    let where_clause = trailing_where_clause(&mut generics);
+    let (impl_generics, type_generics, _) = generics.split_for_impl();
+    let members = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| field_member(i, f))
+        .collect::<Vec<_>>();
+    // like `#[derive(Default)]`, every type parameter is bound.
+    let type_params = generics.type_params().map(|t| &t.ident);
+    quote! {
+        impl #impl_generics ::pinned_init::DefaultParam for #ident #type_generics
+        #where_clause
+            #(#type_params: ::pinned_init::DefaultParam,)*
+        {
+            #[inline]
+            fn default_param() -> Self {
+                Self {
+                    #(#members: ::pinned_init::DefaultParam::default_param(),)*
+                }
+            }
+        }
+    }
+}
+
 fn derive_begin_init_inner(
     DeriveInput {
         attrs,
@@ -370,12 +1348,14 @@ fn derive_begin_init_inner(
         data,
     }: DeriveInput,
 ) -> TokenStream {
-    let fields;
-    if let Data::Struct(s) = data {
-        fields = s.fields;
-    } else {
-        abort!(ident, "Can only derive BeginInit for structs.");
-    }
+    let ongoing_init_ident = ongoing_init_path(&attrs, &ident);
+    let fields = match data {
+        Data::Struct(s) => s.fields,
+        Data::Enum(e) => {
+            return derive_begin_init_enum(ident, generics, ongoing_init_ident, e.variants, false)
+        }
+        Data::Union(_) => abort!(ident, "Can only derive BeginInit for structs and enums."),
+    };
     let (impl_generics, type_generics, where_clause) = my_split_for_impl(&generics);
     let comma = if type_generics.is_empty() {
         quote! {}
@@ -383,36 +1363,16 @@ fn derive_begin_init_inner(
         quote! {,}
     };
     let ongoing_init_lifetime = quote! {'__ongoing_init};
-    let ongoing_init_ident = attrs
-        .iter()
-        .filter_map(|a| {
-            if let Ok(Meta::List(MetaList { path, nested, .. })) = a.parse_meta() {
-                if path.is_ident("ongoing_init") {
-                    if nested.len() == 1 {
-                        if let NestedMeta::Meta(Meta::Path(path)) = nested.first().unwrap() {
-                            return Some(path.clone());
-                        } else {
-                            emit_error!(nested, "Expected a path.");
-                        }
-                    } else {
-                        emit_error!(nested, "Expected single argument");
-                    }
-                }
-            }
-            None
-        })
-        .reduce(|a, b| {
-            emit_error!(b, "#[ongoing_init] should only be specified once."; note = SpanRange::from_tokens(&a).collapse() => "other #[ongoing_init] here");
-            a
-        }).unwrap_or_else(|| abort!(ident, "Expected #[ongoing_init(<name>)] attribute."));
     let mut bare_fields = vec![];
     let mut bare_init_fields = vec![];
+    let mut bare_init_flags = vec![];
 
-    for field in fields.iter() {
+    for (i, field) in fields.iter().enumerate() {
         if has_outer_attr(field.attrs.iter(), "init") {
-            bare_init_fields.push(field.ident.as_ref().unwrap().clone());
+            bare_init_fields.push(field_member(i, field));
+            bare_init_flags.push(field_ident(i, field));
         } else {
-            bare_fields.push(field.ident.as_ref().unwrap().clone());
+            bare_fields.push(field_member(i, field));
         }
     }
     quote! {
@@ -425,7 +1385,10 @@ fn derive_begin_init_inner(
             ;
 
             #[inline]
-            unsafe fn __begin_init<#ongoing_init_lifetime>(self: &#ongoing_init_lifetime mut Self) -> Self::OngoingInit<#ongoing_init_lifetime>
+            unsafe fn __begin_init<#ongoing_init_lifetime>(
+                self: &#ongoing_init_lifetime mut Self,
+                flags: &#ongoing_init_lifetime Self::Flags,
+            ) -> Self::OngoingInit<#ongoing_init_lifetime>
             where
                 Self: #ongoing_init_lifetime,
             {
@@ -439,7 +1402,7 @@ fn derive_begin_init_inner(
                 unsafe {
                     #ongoing_init_ident {
                         #(#bare_fields: &mut self.#bare_fields,)*
-                        #(#bare_init_fields: ::pinned_init::needs_init::NeedsInit::new_unchecked(&mut self.#bare_init_fields),)*
+                        #(#bare_init_fields: ::pinned_init::needs_init::NeedsInit::new_unchecked(&mut self.#bare_init_fields, &flags.#bare_init_flags),)*
                     }
                 }
             }
@@ -456,12 +1419,17 @@ fn derive_begin_pinned_init_inner(
         data,
     }: DeriveInput,
 ) -> TokenStream {
-    let fields;
-    if let Data::Struct(s) = data {
-        fields = s.fields;
-    } else {
-        abort!(ident, "Can only derive BeginPinnedInit for structs.");
-    }
+    let ongoing_init_ident = ongoing_init_path(&attrs, &ident);
+    let fields = match data {
+        Data::Struct(s) => s.fields,
+        Data::Enum(e) => {
+            return derive_begin_init_enum(ident, generics, ongoing_init_ident, e.variants, true)
+        }
+        Data::Union(_) => abort!(
+            ident,
+            "Can only derive BeginPinnedInit for structs and enums."
+        ),
+    };
     let (impl_generics, type_generics, where_clause) = my_split_for_impl(&generics);
     let comma = if type_generics.is_empty() {
         quote! {}
@@ -469,42 +1437,29 @@ fn derive_begin_pinned_init_inner(
         quote! {,}
     };
     let ongoing_init_lifetime = quote! {'__ongoing_init};
-    let ongoing_init_ident = attrs
-        .iter()
-        .filter_map(|a| {
-            if let Ok(Meta::List(MetaList { path, nested, .. })) = a.parse_meta() {
-                if path.is_ident("ongoing_init") {
-                    if nested.len() == 1 {
-                        if let NestedMeta::Meta(Meta::Path(path)) = nested.first().unwrap() {
-                            return Some(path.clone());
-                        } else {
-                            emit_error!(nested, "Expected a path.");
-                        }
-                    } else {
-                        emit_error!(nested, "Expected single argument");
-                    }
-                }
-            }
-            None
-        })
-        .reduce(|a, b| {
-            emit_error!(b, "#[ongoing_init] should only be specified once."; note = SpanRange::from_tokens(&a).collapse() => "other #[ongoing_init] here");
-            a
-        }).unwrap_or_else(|| abort!(ident, "Expected #[ongoing_init(<name>)] attribute."));
     let mut bare_fields = vec![];
     let mut pinned_fields = vec![];
     let mut bare_init_fields = vec![];
+    let mut bare_init_flags = vec![];
     let mut pinned_init_fields = vec![];
+    let mut pinned_init_flags = vec![];
 
-    for field in fields.iter() {
+    for (i, field) in fields.iter().enumerate() {
+        let member = field_member(i, field);
         match (
             has_outer_attr(field.attrs.iter(), "init"),
             has_outer_attr(field.attrs.iter(), "pin"),
         ) {
-            (true, true) => pinned_init_fields.push(field.ident.as_ref().unwrap().clone()),
-            (false, true) => pinned_fields.push(field.ident.as_ref().unwrap().clone()),
-            (true, false) => bare_init_fields.push(field.ident.as_ref().unwrap().clone()),
-            (false, false) => bare_fields.push(field.ident.as_ref().unwrap().clone()),
+            (true, true) => {
+                pinned_init_fields.push(member);
+                pinned_init_flags.push(field_ident(i, field));
+            }
+            (false, true) => pinned_fields.push(member),
+            (true, false) => {
+                bare_init_fields.push(member);
+                bare_init_flags.push(field_ident(i, field));
+            }
+            (false, false) => bare_fields.push(member),
         }
     }
     quote! {
@@ -517,7 +1472,10 @@ fn derive_begin_pinned_init_inner(
             ;
 
             #[inline]
-            unsafe fn __begin_init<#ongoing_init_lifetime>(self: ::core::pin::Pin<&#ongoing_init_lifetime mut Self>) -> Self::OngoingInit<#ongoing_init_lifetime>
+            unsafe fn __begin_init<#ongoing_init_lifetime>(
+                this: *mut Self,
+                flags: &#ongoing_init_lifetime Self::Flags,
+            ) -> Self::OngoingInit<#ongoing_init_lifetime>
             where
                 Self: #ongoing_init_lifetime,
             {
@@ -528,13 +1486,117 @@ fn derive_begin_pinned_init_inner(
                 Self::__CHECK_ALIGNMENT;
                 Self::__CHECK_SIZE;
                 Self::__CHECK_OFFSETS;
-                let this = self.project();
+                // the fields are projected without creating a reference to the
+                // whole struct, so pointers to it stay valid.
                 unsafe {
                     #ongoing_init_ident {
-                        #(#bare_fields: this.#bare_fields,)*
-                        #(#pinned_fields: this.#pinned_fields,)*
-                        #(#bare_init_fields: ::pinned_init::needs_init::NeedsInit::new_unchecked(this.#bare_init_fields),)*
-                        #(#pinned_init_fields: ::pinned_init::needs_init::NeedsPinnedInit::new_unchecked(this.#pinned_init_fields),)*
+                        #(#bare_fields: &mut *::core::ptr::addr_of_mut!((*this).#bare_fields),)*
+                        #(#pinned_fields: ::core::pin::Pin::new_unchecked(&mut *::core::ptr::addr_of_mut!((*this).#pinned_fields)),)*
+                        #(#bare_init_fields: ::pinned_init::needs_init::NeedsInit::new_unchecked(&mut *::core::ptr::addr_of_mut!((*this).#bare_init_fields), &flags.#bare_init_flags),)*
+                        #(#pinned_init_fields: ::pinned_init::needs_init::NeedsPinnedInit::from_raw(::core::ptr::addr_of_mut!((*this).#pinned_init_fields), &flags.#pinned_init_flags),)*
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// derives `BeginInit` or `BeginPinnedInit` for an enum, the variant of the
+/// value is mapped to the same variant of the OngoingInit enum.
+fn derive_begin_init_enum(
+    ident: Ident,
+    generics: Generics,
+    ongoing_init_ident: Path,
+    variants: punctuated::Punctuated<Variant, Token![,]>,
+    is_pinned: bool,
+) -> TokenStream {
+    let (impl_generics, type_generics, where_clause) = my_split_for_impl(&generics);
+    let comma = if type_generics.is_empty() {
+        quote! {}
+    } else {
+        quote! {,}
+    };
+    let ongoing_init_lifetime = quote! {'__ongoing_init};
+    // the pinned variant only uses a shared reference to find the variant and
+    // the addresses of its fields, the field pointers are then derived from
+    // `this`, so pointers to the enum stay valid.
+    let (begin_init_trait, receiver, this) = if is_pinned {
+        (
+            quote! { BeginPinnedInit },
+            quote! { this: *mut Self },
+            quote! { &*this },
+        )
+    } else {
+        (
+            quote! { BeginInit },
+            quote! { self: &#ongoing_init_lifetime mut Self },
+            quote! { self },
+        )
+    };
+    let mut arms = vec![];
+    for variant in &variants {
+        let variant_ident = &variant.ident;
+        let mut members = vec![];
+        let mut bindings = vec![];
+        let mut values = vec![];
+        for (i, field) in variant.fields.iter().enumerate() {
+            let binding = binding_ident(i);
+            let flag = variant_field_ident(variant_ident, i, field);
+            let init = has_outer_attr(field.attrs.iter(), "init");
+            let pin = has_outer_attr(field.attrs.iter(), "pin");
+            values.push(if is_pinned {
+                let ptr = quote! { ::pinned_init::private::__field_ptr(this, #binding) };
+                match (init, pin) {
+                    (true, true) => quote! {
+                        ::pinned_init::needs_init::NeedsPinnedInit::from_raw(#ptr, &flags.#flag)
+                    },
+                    (true, false) => quote! {
+                        ::pinned_init::needs_init::NeedsInit::new_unchecked(&mut *#ptr, &flags.#flag)
+                    },
+                    (false, true) => quote! { ::core::pin::Pin::new_unchecked(&mut *#ptr) },
+                    (false, false) => quote! { &mut *#ptr },
+                }
+            } else if init {
+                quote! {
+                    ::pinned_init::needs_init::NeedsInit::new_unchecked(#binding, &flags.#flag)
+                }
+            } else {
+                quote! { #binding }
+            });
+            members.push(field_member(i, field));
+            bindings.push(binding);
+        }
+        arms.push(quote! {
+            Self::#variant_ident { #(#members: #bindings,)* } => {
+                #ongoing_init_ident::#variant_ident { #(#members: #values,)* }
+            }
+        });
+    }
+    quote! {
+        impl <#impl_generics> ::pinned_init::private::#begin_init_trait for #ident<#type_generics>
+        #where_clause
+        {
+            type OngoingInit<#ongoing_init_lifetime> = #ongoing_init_ident <#ongoing_init_lifetime #comma #type_generics>
+            where
+                Self: #ongoing_init_lifetime,
+            ;
+
+            #[inline]
+            unsafe fn __begin_init<#ongoing_init_lifetime>(
+                #receiver,
+                flags: &#ongoing_init_lifetime Self::Flags,
+            ) -> Self::OngoingInit<#ongoing_init_lifetime>
+            where
+                Self: #ongoing_init_lifetime,
+            {
+                // need to mention these constants again, because they are not
+                // computed if they are not used.
+                Self::__CHECK_ALIGNMENT;
+                Self::__CHECK_SIZE;
+                Self::__CHECK_OFFSETS;
+                unsafe {
+                    match #this {
+                        #(#arms)*
                     }
                 }
             }
@@ -548,49 +1610,38 @@ fn derive_begin_pinned_init_inner(
 /// - `#[init] => <T as AsUninit>::Uninit`
 /// - `else => T`
 fn make_uninit_fields(fields: Fields) -> Fields {
-    match fields {
-        Fields::Named(FieldsNamed {
-            mut named,
-            brace_token,
-        }) => {
-            named = named
-                .into_iter()
-                .map(|mut f| {
-                    if has_outer_attr(f.attrs.iter(), "init") {
-                        if let Some(a@Attribute { tokens, .. } ) = f.attrs.iter()
-                            .filter(|a| matches!(a.style, AttrStyle::Outer) && a.path.is_ident("uninit"))
-                            .reduce(|a, b| {
-                                emit_error!(a, "Expected at most one #[uninit = <type>] attribute."; note = SpanRange::from_tokens(&b).collapse() => "Other found here.");
-                                a
-                            })
-                        {
-                            let mut tokens = tokens.clone().into_iter();
-                            if let Some(TokenTree::Punct(p)) = tokens.next()  {
-                                if p.as_char() == '=' {
-                                    // consume the '='
-                                } else {
-                                    emit_error!(a, "Expected #[uninit = <type>].");
-                                    tokens = quote!{ () }.into_iter();
-                                }
-                            } else {
-                                emit_error!(a, "Expected #[uninit = <type>].");
-                                tokens = quote!{ () }.into_iter();
-                            }
-                            let tokens = TokenStream::from_iter(tokens);
-                            f.ty = parse_quote! { #tokens };
-                        } else {
-                            let ty = f.ty;
-                            f.ty = parse_quote! { <#ty as ::pinned_init::private::AsUninit>::Uninit };
-                        }
-                    }
-                    f.attrs.retain(|a| !(matches!(a.style, AttrStyle::Outer) && a.path.is_ident("uninit")));
-                    f
+    map_fields(fields, |mut f| {
+        if has_outer_attr(f.attrs.iter(), "init") {
+            if let Some(a@Attribute { tokens, .. } ) = f.attrs.iter()
+                .filter(|a| matches!(a.style, AttrStyle::Outer) && a.path.is_ident("uninit"))
+                .reduce(|a, b| {
+                    emit_error!(a, "Expected at most one #[uninit = <type>] attribute."; note = SpanRange::from_tokens(&b).collapse() => "Other found here.");
+                    a
                 })
-                .collect();
-            Fields::Named(FieldsNamed { named, brace_token })
+            {
+                let mut tokens = tokens.clone().into_iter();
+                if let Some(TokenTree::Punct(p)) = tokens.next()  {
+                    if p.as_char() == '=' {
+                        // consume the '='
+                    } else {
+                        emit_error!(a, "Expected #[uninit = <type>].");
+                        tokens = quote!{ () }.into_iter();
+                    }
+                } else {
+                    emit_error!(a, "Expected #[uninit = <type>].");
+                    tokens = quote!{ () }.into_iter();
+                }
+                let tokens = TokenStream::from_iter(tokens);
+                f.ty = parse_quote! { #tokens };
+            } else {
+                let ty = f.ty;
+                f.ty = parse_quote! { <#ty as ::pinned_init::private::AsUninit>::Uninit };
+            }
         }
-        _ => panic!("Expected named fields!"),
-    }
+        f.attrs
+            .retain(|a| !(matches!(a.style, AttrStyle::Outer) && a.path.is_ident("uninit")));
+        f
+    })
 }
 
 /// Changes the types of the given fields for use in the [`BeginPinnedInit::OngoingInit`] type.
@@ -600,27 +1651,25 @@ fn make_uninit_fields(fields: Fields) -> Fields {
 /// - `#[init] => NeedsInit<T>`
 /// - `none => &mut T`
 fn make_ongoing_init_fields(fields: Fields, ongoing_init_lifetime: &TokenStream) -> Fields {
-    match fields {
-        Fields::Named(FieldsNamed {
-            mut named,
-            brace_token,
-        }) => {
-            named = named
-                .into_iter()
-                .map(|mut f| {
-                    let ty = f.ty;
-                    f.ty = match (has_outer_attr(f.attrs.iter(), "init"), has_outer_attr(f.attrs.iter(), "pin")) {
-                        (true, true) => parse_quote! { ::pinned_init::needs_init::NeedsPinnedInit<#ongoing_init_lifetime, #ty> },
-                        (true, false) => parse_quote! { ::pinned_init::needs_init::NeedsInit<#ongoing_init_lifetime, #ty> },
-                        (false, true) => parse_quote! { ::core::pin::Pin<&#ongoing_init_lifetime mut #ty> },
-                        (false, false) => parse_quote! { &#ongoing_init_lifetime mut #ty },
-                    };
-                    f.attrs.retain(|a| !(matches!(a.style, AttrStyle::Outer) && (a.path.is_ident("init") || a.path.is_ident("pin"))));
-                    f
-                })
-                .collect();
-            Fields::Named(FieldsNamed { named, brace_token })
-        }
-        _ => panic!("Expected named fields!"),
-    }
+    map_fields(fields, |mut f| {
+        let ty = f.ty;
+        f.ty = match (
+            has_outer_attr(f.attrs.iter(), "init"),
+            has_outer_attr(f.attrs.iter(), "pin"),
+        ) {
+            (true, true) => {
+                parse_quote! { ::pinned_init::needs_init::NeedsPinnedInit<#ongoing_init_lifetime, #ty> }
+            }
+            (true, false) => {
+                parse_quote! { ::pinned_init::needs_init::NeedsInit<#ongoing_init_lifetime, #ty> }
+            }
+            (false, true) => parse_quote! { ::core::pin::Pin<&#ongoing_init_lifetime mut #ty> },
+            (false, false) => parse_quote! { &#ongoing_init_lifetime mut #ty },
+        };
+        f.attrs.retain(|a| {
+            !(matches!(a.style, AttrStyle::Outer)
+                && (a.path.is_ident("init") || a.path.is_ident("pin")))
+        });
+        f
+    })
 }
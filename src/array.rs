@@ -0,0 +1,198 @@
+//! Support for initializing arrays element by element.
+//!
+//! The elements of an array are initialized in order, each with its own
+//! parameter. When the initialization of an element fails or panics, the
+//! elements that were already initialized are dropped in reverse order.
+
+use crate::{
+    needs_init::{NeedsInit, NeedsPinnedInit},
+    private::{AsUninit, BeginInit, BeginPinnedInit, PartialInit},
+    transmute::TransmuteInto,
+    DefaultParam, Init, PinnedDeinit, PinnedInit, TryInit, TryPinnedInit,
+};
+use core::array;
+
+// SAFETY: `T::Uninit` has the same layout as `T`, so `[T::Uninit; N]` has the
+// same layout as `[T; N]`.
+unsafe impl<T: AsUninit, const N: usize> AsUninit for [T; N] {
+    type Uninit = [T::Uninit; N];
+}
+
+// SAFETY: `T` has the same layout as `U`, so `[T; N]` has the same layout as
+// `[U; N]`. The caller guarantees, that every element satisfies the invariants
+// of `U`.
+unsafe impl<T: TransmuteInto<U>, U, const N: usize> TransmuteInto<[U; N]> for [T; N] {
+    #[inline]
+    unsafe fn transmute_ptr(this: *const Self) -> *const [U; N] {
+        this as *const [U; N]
+    }
+}
+
+// SAFETY: every element is tracked by its own flags.
+unsafe impl<T: PartialInit, const N: usize> PartialInit for [T; N] {
+    type Flags = [T::Flags; N];
+
+    #[inline]
+    fn __new_flags() -> Self::Flags {
+        array::from_fn(|_| T::__new_flags())
+    }
+
+    #[inline]
+    unsafe fn __is_init(this: *const Self, flags: &Self::Flags) -> bool {
+        flags.iter().enumerate().all(|(i, flags)| unsafe {
+            // SAFETY: `i < N`, so the pointer stays inside of the array.
+            T::__is_init((this as *const T).add(i), flags)
+        })
+    }
+
+    #[inline]
+    unsafe fn __is_uninit(this: *const Self, flags: &Self::Flags) -> bool {
+        flags.iter().enumerate().all(|(i, flags)| unsafe {
+            // SAFETY: `i < N`, so the pointer stays inside of the array.
+            T::__is_uninit((this as *const T).add(i), flags)
+        })
+    }
+
+    #[inline]
+    fn __set_init(flags: &Self::Flags) {
+        flags.iter().for_each(T::__set_init)
+    }
+
+    #[inline]
+    unsafe fn __drop_partial(this: *mut Self, flags: &Self::Flags) {
+        // the elements are initialized in order, so they are dropped in reverse.
+        for (i, flags) in flags.iter().enumerate().rev() {
+            unsafe {
+                // SAFETY: `i < N`, so the pointer stays inside of the array and
+                // the caller guarantees that the array is valid for dropping.
+                T::__drop_partial((this as *mut T).add(i), flags)
+            }
+        }
+    }
+}
+
+// SAFETY: every element is deinitialized in place.
+unsafe impl<U: PinnedDeinit<T>, T, const N: usize> PinnedDeinit<[T; N]> for [U; N] {
+    #[inline]
+    unsafe fn deinit_raw(this: *mut [T; N]) {
+        // the elements are initialized in order, so they are deinitialized in
+        // reverse.
+        for i in (0..N).rev() {
+            unsafe {
+                // SAFETY: `i < N`, so the pointer stays inside of the array and
+                // the caller upholds the requirements for every element.
+                U::deinit_raw((this as *mut T).add(i))
+            }
+        }
+    }
+}
+
+impl<T: PartialInit, const N: usize> BeginPinnedInit for [T; N] {
+    type OngoingInit<'init>
+        = [NeedsPinnedInit<'init, T>; N]
+    where
+        Self: 'init;
+
+    #[inline]
+    unsafe fn __begin_init<'init>(
+        this: *mut Self,
+        flags: &'init Self::Flags,
+    ) -> Self::OngoingInit<'init>
+    where
+        Self: 'init,
+    {
+        array::from_fn(|i| unsafe {
+            // SAFETY: the elements of a pinned array are structurally pinned and
+            // `i < N`. The caller guarantees, that the array changes its type
+            // and that `flags` track its initialization, this extends to every
+            // element.
+            NeedsPinnedInit::from_raw((this as *mut T).add(i), &flags[i])
+        })
+    }
+}
+
+impl<T: PartialInit, const N: usize> BeginInit for [T; N] {
+    type OngoingInit<'init>
+        = [NeedsInit<'init, T>; N]
+    where
+        Self: 'init;
+
+    #[inline]
+    unsafe fn __begin_init<'init>(
+        &'init mut self,
+        flags: &'init Self::Flags,
+    ) -> Self::OngoingInit<'init>
+    where
+        Self: 'init,
+    {
+        let mut elems = self.iter_mut().zip(flags);
+        array::from_fn(|_| {
+            let (elem, flags) = elems.next().unwrap();
+            unsafe {
+                // SAFETY: the caller guarantees, that the array changes its type
+                // and that `flags` track its initialization, this extends to
+                // every element.
+                NeedsInit::new_unchecked(elem, flags)
+            }
+        })
+    }
+}
+
+impl<T: PinnedInit, const N: usize> PinnedInit for [T; N] {
+    type Initialized = [T::Initialized; N];
+    type Param = [T::Param; N];
+
+    #[inline]
+    fn init_raw(this: NeedsPinnedInit<Self>, param: Self::Param) {
+        for (elem, param) in this.begin_init().into_iter().zip(param) {
+            T::init_raw(elem, param);
+        }
+    }
+}
+
+impl<T: TryPinnedInit, const N: usize> TryPinnedInit for [T; N] {
+    type Initialized = [T::Initialized; N];
+    type Param = [T::Param; N];
+    type Error = T::Error;
+
+    #[inline]
+    fn try_init_raw(this: NeedsPinnedInit<Self>, param: Self::Param) -> Result<(), T::Error> {
+        for (elem, param) in this.begin_init().into_iter().zip(param) {
+            T::try_init_raw(elem, param)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Init, const N: usize> Init for [T; N] {
+    type Initialized = [T::Initialized; N];
+    type Param = [T::Param; N];
+
+    #[inline]
+    fn init_raw(this: NeedsInit<Self>, param: Self::Param) {
+        for (elem, param) in this.begin_init().into_iter().zip(param) {
+            T::init_raw(elem, param);
+        }
+    }
+}
+
+impl<T: TryInit, const N: usize> TryInit for [T; N] {
+    type Initialized = [T::Initialized; N];
+    type Param = [T::Param; N];
+    type Error = T::Error;
+
+    #[inline]
+    fn try_init_raw(this: NeedsInit<Self>, param: Self::Param) -> Result<(), T::Error> {
+        for (elem, param) in this.begin_init().into_iter().zip(param) {
+            T::try_init_raw(elem, param)?;
+        }
+        Ok(())
+    }
+}
+
+impl<P: DefaultParam, const N: usize> DefaultParam for [P; N] {
+    #[inline]
+    fn default_param() -> Self {
+        array::from_fn(|_| P::default_param())
+    }
+}
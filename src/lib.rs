@@ -1,82 +1,253 @@
 #![doc = include_str!("lib.md")]
 #![cfg_attr(not(feature = "std"), no_std)]
-#![feature(generic_associated_types)]
+#![feature(generic_associated_types, offset_of_enum)]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
 #![deny(unsafe_op_in_unsafe_fn, missing_docs)]
+#[cfg(feature = "alloc")]
+use crate::{needs_init::NeedsPinnedInitSlice, ptr::AllocUninitSlice};
 use crate::{
     needs_init::{NeedsInit, NeedsPinnedInit},
-    private::{BeginInit, BeginPinnedInit},
-    ptr::OwnedUniquePtr,
+    private::{AsUninit, BeginInit, BeginPinnedInit, PartialInit, WriteUninit},
+    ptr::{AllocUninit, OwnedUniquePtr},
     transmute::TransmuteInto,
 };
-use core::pin::Pin;
+use core::{
+    convert::Infallible,
+    marker::PhantomData,
+    mem::{self, ManuallyDrop, MaybeUninit},
+    pin::Pin,
+};
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
-macro_rules! if_cfg {
-    (if $cfg:tt {$($body:tt)*} else {$($els:tt)*}) => {
-        #[cfg $cfg]
-        {
-            $($body)*
-        }
-        #[cfg(not $cfg)]
-        {
-            $($els)*
-        }
-    };
-}
-
+mod array;
 pub mod needs_init;
+#[cfg(feature = "alloc")]
+pub mod pinned_vec;
 pub mod ptr;
+pub mod slot;
+pub mod stack;
+pub mod static_uninit;
+mod wrappers;
 
-/// Use this attribute on a struct with named fields to ensure safe
-/// pinned initialization of all the fields marked with `#[init]`.
+/// Use this attribute on a struct with named or unnamed fields or on an enum to
+/// ensure safer pinned initialization of all the fields marked with `#[init]`.
 ///
 /// This attribute does several things, it:
-/// - `#[pin_project]`s your struct, structually pinning all fields with `#[init]` implicitly (adding `#[pin]`).
+/// - `#[pin_project]`s your struct, structually pinning all fields with `#[pin]`.
 /// - adds a constant type parameter of type bool with a default value of true.
-/// This constant type parameter indicates if your struct is in an initialized
-/// (and thus also pinned) state. A type alias `{your-struct-name}Uninit` is
-/// created to refer to the uninitialized variant more ergonomically, it should
-/// always be used instead of specifying the const parameter.
+///   This constant type parameter indicates if your struct is in an initialized
+///   (and thus also pinned) state. A type alias `{your-struct-name}Uninit` is
+///   created to refer to the uninitialized variant more ergonomically, it should
+///   always be used instead of specifying the const parameter.
 /// - propagates that const parameter to all fields marked with `#[init]`.
-/// - implements [`PinnedInit`] for your struct delegating to all fields marked
-/// with `#[init]`.
-/// - implements [`TransmuteInto<{your-struct-name}>`]()
-/// `for`{your-struct-name}Uninit` and checks for layout equivalence between the
-/// two.
+/// - implements [`TransmuteInto<{your-struct-name}>`]
+///   `for`{your-struct-name}Uninit` and checks for layout equivalence between the
+///   two.
 /// - creates a custom type borrowing from your struct that is used as the
-/// `OngoingInit` type for the [`BeginPinnedInit`] trait.
+///   `OngoingInit` type for the [`BeginPinnedInit`] trait.
 /// - implements [`BeginPinnedInit`] for your struct.
+/// - implements [`PinnedDeinit`] for pinned structs, when `deinit` is passed
+///   to the attribute.
+/// - for enums, the `OngoingInit` type is an enum with the same variants.
+///
+/// The only thing you need to implement is [`PinnedInit`].
 ///
 /// Then you can safely, soundly and ergonomically initialize a value of such a
 /// struct behind an [`OwnedUniquePtr<{your-struct-name}>`]:
-/// TODO example
+/// ```rust
+/// # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+/// use core::{mem::MaybeUninit, pin::Pin};
+/// use pinned_init::prelude::*;
+///
+/// #[manual_init(pinned)]
+/// pub struct Timer {
+///     #[init]
+///     deadline: u64,
+/// }
+///
+/// impl PinnedInit for TimerUninit {
+///     type Initialized = Timer;
+///     type Param = u64;
+///
+///     fn init_raw(this: NeedsPinnedInit<Self>, deadline: u64) {
+///         let TimerOngoingInit { deadline: slot } = this.begin_init();
+///         slot.init(deadline);
+///     }
+/// }
+///
+/// #[manual_init(pinned)]
+/// pub struct Alarm {
+///     #[pin]
+///     #[init]
+///     timer: Timer,
+///     #[init]
+///     armed: bool,
+///     label: &'static str,
+/// }
+///
+/// impl PinnedInit for AlarmUninit {
+///     type Initialized = Alarm;
+///     type Param = u64;
+///
+///     fn init_raw(this: NeedsPinnedInit<Self>, deadline: u64) {
+///         let AlarmOngoingInit { timer, armed, label } = this.begin_init();
+///         timer.init_with(deadline);
+///         armed.init(!label.is_empty());
+///     }
+/// }
+///
+/// let uninit = AlarmUninit {
+///     timer: TimerUninit { deadline: MaybeUninit::uninit() },
+///     armed: MaybeUninit::uninit(),
+///     label: "wake up",
+/// };
+/// let alarm: Pin<Box<Alarm>> = Box::pin(uninit).init_with(30);
+/// assert!(alarm.armed && alarm.timer.deadline == 30);
+///
+/// #[manual_init(pinned)]
+/// pub enum Slot {
+///     Empty,
+///     Armed(#[pin] #[init] Timer),
+/// }
+///
+/// impl PinnedInit for SlotUninit {
+///     type Initialized = Slot;
+///     type Param = u64;
+///
+///     fn init_raw(this: NeedsPinnedInit<Self>, deadline: u64) {
+///         match this.begin_init() {
+///             SlotOngoingInit::Empty => {}
+///             SlotOngoingInit::Armed(timer) => {
+///                 timer.init_with(deadline);
+///             }
+///         }
+///     }
+/// }
+///
+/// let uninit = SlotUninit::Armed(TimerUninit { deadline: MaybeUninit::uninit() });
+/// let slot: Pin<Box<Slot>> = Box::pin(uninit).init_with(7);
+/// assert!(matches!(&*slot, Slot::Armed(timer) if timer.deadline == 7));
+/// ```
 pub use pinned_init_macro::manual_init;
 
-/// Use this attribute on a struct with named fields to ensure safer
-/// pinned initialization of all the fields marked with `#[init]`.
+/// Use this attribute on a struct with named or unnamed fields or on an enum to
+/// ensure safe pinned initialization of all the fields marked with `#[init]`.
 ///
 /// This attribute does several things, it:
-/// - `#[pin_project]`s your struct, structually pinning all fields with `#[pin]`.
+/// - `#[pin_project]`s your struct, structually pinning all fields with `#[init]` implicitly (adding `#[pin]`),
+///   except for fields marked with `#[init(unpinned)]`, these are initialized via `Init`.
 /// - adds a constant type parameter of type bool with a default value of true.
-/// This constant type parameter indicates if your struct is in an initialized
-/// (and thus also pinned) state. A type alias `{your-struct-name}Uninit` is
-/// created to refer to the uninitialized variant more ergonomically, it should
-/// always be used instead of specifying the const parameter.
+///   This constant type parameter indicates if your struct is in an initialized
+///   (and thus also pinned) state. A type alias `{your-struct-name}Uninit` is
+///   created to refer to the uninitialized variant more ergonomically, it should
+///   always be used instead of specifying the const parameter.
 /// - propagates that const parameter to all fields marked with `#[init]`.
-/// - implements [`TransmuteInto<{your-struct-name}>`]
-/// `for`{your-struct-name}Uninit` and checks for layout equivalence between the
-/// two.
+/// - implements [`PinnedInit`] for your struct delegating to all fields marked
+///   with `#[init]`.
+/// - creates a `{your-struct-name}InitParams` struct with a member for the
+///   parameter of every field marked with `#[init]`, used as the parameter for
+///   `PinnedInit`. Its builder uses the default parameter of every member, that
+///   was not set.
+/// - supports `#[init(param = <expr>)]` and `#[init(with = <fn>)]` to compute
+///   the parameter of a field or to initialize it with a custom function.
+/// - supports `param = { <fields> }` (`param = ( <types> )` for tuple structs) as
+///   an argument, adding members to `{your-struct-name}InitParams` that the
+///   expressions of `#[init(...)]` can use.
+/// - implements [`TransmuteInto<{your-struct-name}>`]()
+///   `for`{your-struct-name}Uninit` and checks for layout equivalence between the
+///   two.
 /// - creates a custom type borrowing from your struct that is used as the
-/// `OngoingInit` type for the [`BeginPinnedInit`] trait.
+///   `OngoingInit` type for the [`BeginPinnedInit`] trait.
 /// - implements [`BeginPinnedInit`] for your struct.
-///
-/// The only thing you need to implement is [`PinnedInit`].
+/// - implements [`PinnedDeinit`] for pinned structs, when `deinit` is passed
+///   to the attribute.
+/// - for enums, the `OngoingInit` type is an enum with the same variants and the
+///   `InitParams` struct has a member with the init params of every variant.
 ///
 /// Then you can safely, soundly and ergonomically initialize a value of such a
 /// struct behind an [`OwnedUniquePtr<{your-struct-name}>`]:
-/// TODO example
+/// ```rust
+/// # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+/// use core::{mem::MaybeUninit, pin::Pin};
+/// use pinned_init::prelude::*;
+///
+/// #[manual_init(pinned)]
+/// pub struct Timer {
+///     #[init]
+///     deadline: u64,
+/// }
+///
+/// impl PinnedInit for TimerUninit {
+///     type Initialized = Timer;
+///     type Param = u64;
+///
+///     fn init_raw(this: NeedsPinnedInit<Self>, deadline: u64) {
+///         let TimerOngoingInit { deadline: slot } = this.begin_init();
+///         slot.init(deadline);
+///     }
+/// }
+///
+/// #[manual_init]
+/// pub struct Id {
+///     #[init]
+///     value: u32,
+/// }
+///
+/// impl Init for IdUninit {
+///     type Initialized = Id;
+///     type Param = ();
+///
+///     fn init_raw(this: NeedsInit<Self>, _: ()) {
+///         let IdOngoingInit { value } = this.begin_init();
+///         value.init(1);
+///     }
+/// }
+///
+/// fn init_never(timer: NeedsPinnedInit<'_, TimerUninit>) {
+///     PinnedInit::init_raw(timer, u64::MAX);
+/// }
+///
+/// #[pinned_init]
+/// pub struct Session {
+///     #[init]
+///     timeout: Timer,
+///     #[init(param = timeout.deadline + 10)]
+///     grace: Timer,
+///     #[init(with = init_never)]
+///     expiry: Timer,
+///     #[init(unpinned)]
+///     id: Id,
+///     name: &'static str,
+/// }
+///
+/// let uninit = SessionUninit {
+///     timeout: TimerUninit { deadline: MaybeUninit::uninit() },
+///     grace: TimerUninit { deadline: MaybeUninit::uninit() },
+///     expiry: TimerUninit { deadline: MaybeUninit::uninit() },
+///     id: IdUninit { value: MaybeUninit::uninit() },
+///     name: "admin",
+/// };
+/// // `id` is not set, so it uses its default parameter `()`.
+/// let params = SessionInitParams::builder().with_timeout(20).build();
+/// let session: Pin<Box<Session>> = Box::pin(uninit).init_with(params);
+/// assert_eq!((session.timeout.deadline, session.grace.deadline), (20, 30));
+/// assert_eq!((session.expiry.deadline, session.id.value), (u64::MAX, 1));
+///
+/// #[pinned_init]
+/// pub enum State {
+///     Idle,
+///     Running(#[init] Timer),
+/// }
+///
+/// let uninit = StateUninit::Running(TimerUninit { deadline: MaybeUninit::uninit() });
+/// let state: Pin<Box<State>> = Box::pin(uninit).init_with(StateInitParams {
+///     running: StateRunningInitParams(5),
+///     ..Default::default()
+/// });
+/// assert!(matches!(&*state, State::Running(timer) if timer.deadline == 5));
+/// ```
 pub use pinned_init_macro::pinned_init;
 
 #[doc(hidden)]
@@ -85,7 +256,8 @@ pub mod prelude {
     pub use crate::{
         manual_init,
         needs_init::{NeedsInit, NeedsPinnedInit},
-        pinned_init, Init, PinnedInit, SafePinnedInit,
+        pinned_init, stack_init, DefaultParam, Init, PinnedInit, PinnedReinit, SafePinnedInit,
+        TryInit, TryPinnedInit,
     };
 }
 
@@ -96,7 +268,7 @@ pub mod __private {
 
 #[doc(hidden)]
 pub mod private {
-    use core::pin::Pin;
+    use core::{cell::Cell, mem::MaybeUninit, ptr};
 
     pub use pinned_init_macro::{BeginInit, BeginPinnedInit};
 
@@ -105,7 +277,7 @@ pub mod private {
     ///
     /// [`pinned_init`]: crate::pinned_init
     /// [`manual_init`]: crate::manual_init
-    pub trait BeginPinnedInit {
+    pub trait BeginPinnedInit: PartialInit {
         #[doc(hidden)]
         type OngoingInit<'init>: 'init
         where
@@ -115,15 +287,27 @@ pub mod private {
         ///
         /// # Safety
         ///
-        /// - When the `'init` lifetime expires, the value at `inner` will be
-        /// initialized and have changed type to `T::Initialized`.
+        /// - When the `'init` lifetime expires, the value at `inner` will either be
+        ///   initialized and have changed type to `T::Initialized`, or the
+        ///   initialization failed and the value will be dropped according to `flags`.
         /// - From the moment this function is called until the end of `'init` the
-        /// produced [`NeedsPinnedInit`] becomes the only valid way to access the
-        /// underlying value.
+        ///   produced [`NeedsPinnedInit`] becomes the only valid way to access the
+        ///   underlying value.
         /// - The caller needs to guarantee, that the pointer from which `inner` was
-        /// derived changes its pointee type to `T::Initialized`, when `'init` ends.
+        ///   derived changes its pointee type to `T::Initialized`, when `'init` ends.
+        /// - `flags` need to track the initialization of the value at `inner`.
+        /// - `this` needs to be valid for reads and writes and pinned for `'init`.
+        ///
+        /// Implementations need to derive the pointers to the fields from `this`
+        /// without creating a reference to the whole value, otherwise raw
+        /// pointers to the value that were created earlier would be invalidated.
+        ///
+        /// [`NeedsPinnedInit`]: crate::needs_init::NeedsPinnedInit
         #[doc(hidden)]
-        unsafe fn __begin_init<'init>(self: Pin<&'init mut Self>) -> Self::OngoingInit<'init>
+        unsafe fn __begin_init<'init>(
+            this: *mut Self,
+            flags: &'init Self::Flags,
+        ) -> Self::OngoingInit<'init>
         where
             Self: 'init;
     }
@@ -133,7 +317,7 @@ pub mod private {
     ///
     /// [`pinned_init`]: crate::pinned_init
     /// [`manual_init`]: crate::manual_init
-    pub trait BeginInit {
+    pub trait BeginInit: PartialInit {
         #[doc(hidden)]
         type OngoingInit<'init>: 'init
         where
@@ -143,19 +327,114 @@ pub mod private {
         ///
         /// # Safety
         ///
-        /// - When the `'init` lifetime expires, the value at `inner` will be
-        /// initialized and have changed type to `T::Initialized`.
+        /// - When the `'init` lifetime expires, the value at `inner` will either be
+        ///   initialized and have changed type to `T::Initialized`, or the
+        ///   initialization failed and the value will be dropped according to `flags`.
         /// - From the moment this function is called until the end of `'init` the
-        /// produced [`NeedsPinnedInit`] becomes the only valid way to access the
-        /// underlying value.
+        ///   produced [`NeedsInit`] becomes the only valid way to access the
+        ///   underlying value.
         /// - The caller needs to guarantee, that the pointer from which `inner` was
-        /// derived changes its pointee type to `T::Initialized`, when `'init` ends.
+        ///   derived changes its pointee type to `T::Initialized`, when `'init` ends.
+        /// - `flags` need to track the initialization of the value at `inner`.
+        ///
+        /// [`NeedsInit`]: crate::needs_init::NeedsInit
         #[doc(hidden)]
-        unsafe fn __begin_init<'init>(self: &'init mut Self) -> Self::OngoingInit<'init>
+        unsafe fn __begin_init<'init>(
+            self: &'init mut Self,
+            flags: &'init Self::Flags,
+        ) -> Self::OngoingInit<'init>
         where
             Self: 'init;
     }
 
+    /// Tracks which parts of a value have already been initialized, so that the value
+    /// can be dropped, when its initialization fails midway. Automatically implemented
+    /// by [`manual_init`] and [`pinned_init`].
+    ///
+    /// # Safety
+    ///
+    /// - [`Self::__drop_partial`] must drop exactly those parts of the value, that are
+    ///   still alive according to the given flags.
+    /// - [`Self::__is_init`] may only return `true`, if the value can be transmuted into
+    ///   its initialized form.
+    ///
+    /// [`pinned_init`]: crate::pinned_init
+    /// [`manual_init`]: crate::manual_init
+    pub unsafe trait PartialInit {
+        /// The flags tracking the initialization of each part of `Self`.
+        type Flags;
+
+        /// Creates new flags, marking everything as uninitialized.
+        fn __new_flags() -> Self::Flags;
+
+        /// Returns `true` if all parts of the value are initialized.
+        ///
+        /// # Safety
+        ///
+        /// - `this` needs to be valid for reads (it is only used to read the
+        ///   discriminant of enums).
+        /// - `flags` need to track the initialization of the value at `this`.
+        unsafe fn __is_init(this: *const Self, flags: &Self::Flags) -> bool;
+
+        /// Returns `true` if no part of the value has been initialized yet.
+        ///
+        /// # Safety
+        ///
+        /// - `this` needs to be valid for reads (it is only used to read the
+        ///   discriminant of enums).
+        /// - `flags` need to track the initialization of the value at `this`.
+        unsafe fn __is_uninit(this: *const Self, flags: &Self::Flags) -> bool;
+
+        /// Marks all parts of the value as initialized.
+        fn __set_init(flags: &Self::Flags);
+
+        /// Drops the value at `this` in place, respecting which parts are already
+        /// initialized.
+        ///
+        /// # Safety
+        ///
+        /// - `this` needs to be valid for dropping the value.
+        /// - `flags` need to track the initialization of the value at `this`.
+        /// - the value at `this` must not be used afterwards.
+        unsafe fn __drop_partial(this: *mut Self, flags: &Self::Flags);
+    }
+
+    // SAFETY: the flag is only set, when the value has been written.
+    unsafe impl<T> PartialInit for MaybeUninit<T> {
+        type Flags = Cell<bool>;
+
+        #[inline]
+        fn __new_flags() -> Self::Flags {
+            Cell::new(false)
+        }
+
+        #[inline]
+        unsafe fn __is_init(_: *const Self, flags: &Self::Flags) -> bool {
+            flags.get()
+        }
+
+        #[inline]
+        unsafe fn __is_uninit(_: *const Self, flags: &Self::Flags) -> bool {
+            !flags.get()
+        }
+
+        #[inline]
+        fn __set_init(flags: &Self::Flags) {
+            flags.set(true);
+        }
+
+        #[inline]
+        unsafe fn __drop_partial(this: *mut Self, flags: &Self::Flags) {
+            if flags.get() {
+                unsafe {
+                    // SAFETY: the value was initialized and the caller guarantees,
+                    // that `this` is valid for dropping.
+                    ptr::drop_in_place(this as *mut T)
+                }
+            }
+        }
+    }
+
     /// Marks types that have an uninitialized form, automatically implemented by [`manual_init`]
     /// and [`pinned_init`].
     ///
@@ -165,6 +444,118 @@ pub mod private {
         /// Uninitialized form of `Self`.
         type Uninit: crate::transmute::TransmuteInto<Self>;
     }
+
+    /// Trait implemented by the [`pinned_init`] and the [`manual_init`] proc
+    /// macros to write the fields of an uninitialized value in place. This
+    /// trait should not be implemented manually.
+    ///
+    /// # Safety
+    ///
+    /// - every slot created by [`Self::__slots`] needs to point to the field
+    ///   of the value at `this` with the same type and offset.
+    /// - [`Self::__is_written`] may only return `true`, when every field has
+    ///   been written through its slot and thus is initialized.
+    /// - [`Self::__drop_written`] must only drop the fields, that have been
+    ///   written.
+    ///
+    /// [`pinned_init`]: crate::pinned_init
+    /// [`manual_init`]: crate::manual_init
+    pub unsafe trait WriteUninit: Sized {
+        /// The slots of the fields of `Self`.
+        type Slots<'a>
+        where
+            Self: 'a;
+        /// Tracks which fields have been written.
+        type WriteFlags;
+
+        /// Creates new flags, no field has been written.
+        fn __new_write_flags() -> Self::WriteFlags;
+
+        /// Creates the slots of the value at `this`.
+        ///
+        /// # Safety
+        ///
+        /// - `this` needs to be valid for writes for the duration of `'a`.
+        /// - `flags` need to be fresh.
+        unsafe fn __slots<'a>(this: *mut Self, flags: &'a Self::WriteFlags) -> Self::Slots<'a>;
+
+        /// Checks if all fields have been written.
+        fn __is_written(flags: &Self::WriteFlags) -> bool;
+
+        /// Drops all fields that have been written in reverse order.
+        ///
+        /// # Safety
+        ///
+        /// - `flags` need to track the writes to the value at `this`.
+        /// - the value at `this` must not be used afterwards.
+        unsafe fn __drop_written(this: *mut Self, flags: &Self::WriteFlags);
+    }
+
+    /// Returns a pointer to `field` with the provenance of `this`. Used by the
+    /// [`pinned_init`] and the [`manual_init`] proc macros to project the
+    /// fields of an enum, the reference to the enum is only used to find the
+    /// variant.
+    ///
+    /// # Safety
+    ///
+    /// `field` needs to point into the value at `this`.
+    ///
+    /// [`pinned_init`]: crate::pinned_init
+    /// [`manual_init`]: crate::manual_init
+    #[inline]
+    pub unsafe fn __field_ptr<T, F>(this: *mut T, field: &F) -> *mut F {
+        unsafe {
+            // SAFETY: the caller guarantees, that `field` points into the value
+            // at `this`.
+            let offset = (field as *const F as *const u8).offset_from(this as *const u8);
+            (this as *mut u8).offset(offset) as *mut F
+        }
+    }
+
+    /// Turns a member of the `{your-struct-name}InitParams` struct generated by
+    /// [`pinned_init`] into its parameter, members that are still [`Unset`]
+    /// use their [`DefaultParam`]. Used by the generated `build` function.
+    ///
+    /// [`pinned_init`]: crate::pinned_init
+    /// [`Unset`]: crate::Unset
+    /// [`DefaultParam`]: crate::DefaultParam
+    pub trait BuildParam<T> {
+        /// api internal function, do not call from outside this library!
+        fn __build_param(self) -> T;
+    }
+
+    impl<T> BuildParam<T> for T {
+        #[inline]
+        fn __build_param(self) -> T {
+            self
+        }
+    }
+
+    impl<T: crate::DefaultParam> BuildParam<T> for crate::Unset {
+        #[inline]
+        fn __build_param(self) -> T {
+            T::default_param()
+        }
+    }
+
+    macro_rules! as_maybe_uninit {
+        ($({$($generics:tt)*} $ty:ty),* $(,)?) => {
+            $(
+                // SAFETY: `MaybeUninit<T>` has the same layout as `T`.
+                unsafe impl<$($generics)*> AsUninit for $ty {
+                    type Uninit = MaybeUninit<$ty>;
+                }
+            )*
+        };
+    }
+
+    as_maybe_uninit! {
+        {} bool, {} char,
+        {} u8, {} u16, {} u32, {} u64, {} u128, {} usize,
+        {} i8, {} i16, {} i32, {} i64, {} i128, {} isize,
+        {} f32, {} f64,
+        {T: ?Sized} *const T, {T: ?Sized} *mut T, {T: ?Sized} core::ptr::NonNull<T>,
+    }
 }
 
 /// Initializing a value in place (because it is pinned) requires a
@@ -172,7 +563,10 @@ pub mod private {
 /// to create a safer abstraction and requires users to explicitly opt in to use
 /// this initialization.
 pub mod transmute {
-    use core::{mem, pin::Pin};
+    use core::{
+        mem::{self, MaybeUninit},
+        pin::Pin,
+    };
 
     /// Marks and allows easier unsafe transmutation between types.
     /// This trait should **not** be implemented manually.
@@ -257,6 +651,15 @@ pub mod transmute {
             }
         }
     }
+
+    // SAFETY: [`MaybeUninit<T>`] has the same layout as `T`. The caller needs to
+    // ensure that the value is initialized.
+    unsafe impl<T> TransmuteInto<T> for MaybeUninit<T> {
+        #[inline]
+        unsafe fn transmute_ptr(this: *const Self) -> *const T {
+            this as *const T
+        }
+    }
 }
 
 /// Facilitates pinned initialization.
@@ -265,10 +668,12 @@ pub mod transmute {
 /// fashion in many cases.
 ///
 /// You will need to implement this trait yourself, if your struct contains any
-/// fields with the [`static_uninit::StaticUninit`] type. When implementing this
+/// fields with the [`StaticUninit`] type. When implementing this
 /// trait manually, use the [`manual_init`] proc macro attribute to implement
 /// [`BeginPinnedInit`] for your struct, as implementing that trait is not supposed to
 /// be done manually.
+///
+/// [`StaticUninit`]: static_uninit::StaticUninit
 pub trait PinnedInit: TransmuteInto<Self::Initialized> + BeginPinnedInit {
     /// The initialized version of `Self`. `Self` can be transmuted via
     /// [`TransmuteInto`] into this type.
@@ -282,6 +687,31 @@ pub trait PinnedInit: TransmuteInto<Self::Initialized> + BeginPinnedInit {
     fn init_raw(this: NeedsPinnedInit<Self>, param: Self::Param);
 }
 
+/// Facilitates fallible pinned initialization.
+///
+/// This is the fallible counterpart of [`PinnedInit`], use it when initializing
+/// `Self` can fail (e.g. an FFI call returning an error code). Implementing this
+/// trait works the same way as implementing [`PinnedInit`], use the [`manual_init`]
+/// proc macro attribute to implement [`BeginPinnedInit`] for your struct.
+///
+/// When [`Self::try_init_raw`] returns an error, you do not need to clean up: all
+/// fields that were already initialized are dropped in reverse order, the remaining
+/// fields are dropped in their uninitialized form.
+pub trait TryPinnedInit: TransmuteInto<Self::Initialized> + BeginPinnedInit {
+    /// The initialized version of `Self`. `Self` can be transmuted via
+    /// [`TransmuteInto`] into this type.
+    type Initialized;
+    /// An optional Parameter used to initialize `Self`.
+    /// When you do not need it, set to `()`
+    type Param;
+    /// The error that can occur while initializing `Self`.
+    type Error;
+
+    /// Initialize the value behind the given pointer with the given parameter, this pointer ensures,
+    /// that `Self` really will be initialized, when `Ok(())` is returned.
+    fn try_init_raw(this: NeedsPinnedInit<Self>, param: Self::Param) -> Result<(), Self::Error>;
+}
+
 #[cfg(feature = "unsafe-alias-cell")]
 impl<T: PinnedInit> PinnedInit for unsafe_alias_cell::UnsafeAliasCell<T> {
     type Initialized = unsafe_alias_cell::UnsafeAliasCell<T::Initialized>;
@@ -290,9 +720,27 @@ impl<T: PinnedInit> PinnedInit for unsafe_alias_cell::UnsafeAliasCell<T> {
     fn init_raw(this: NeedsPinnedInit<Self>, param: Self::Param) {
         T::init_raw(
             unsafe {
-                // SAFETY: we do not move out of the arg, we do not move out of the returned value
-                // and the wrapping value is fully initialized
-                this.map_unchecked(|this| unsafe { &mut *this.get() })
+                // SAFETY: the contents of an `UnsafeAliasCell` are structurally pinned and the
+                // wrapping value is fully initialized
+                this.map_ptr_unchecked(|this| unsafe_alias_cell::UnsafeAliasCell::raw_get(this))
+            },
+            param,
+        )
+    }
+}
+
+#[cfg(feature = "unsafe-alias-cell")]
+impl<T: TryPinnedInit> TryPinnedInit for unsafe_alias_cell::UnsafeAliasCell<T> {
+    type Initialized = unsafe_alias_cell::UnsafeAliasCell<T::Initialized>;
+    type Param = T::Param;
+    type Error = T::Error;
+
+    fn try_init_raw(this: NeedsPinnedInit<Self>, param: Self::Param) -> Result<(), T::Error> {
+        T::try_init_raw(
+            unsafe {
+                // SAFETY: the contents of an `UnsafeAliasCell` are structurally pinned and the
+                // wrapping value is fully initialized
+                this.map_ptr_unchecked(|this| unsafe_alias_cell::UnsafeAliasCell::raw_get(this))
             },
             param,
         )
@@ -313,13 +761,67 @@ unsafe impl<U, T: TransmuteInto<U>> TransmuteInto<unsafe_alias_cell::UnsafeAlias
 
 #[cfg(feature = "unsafe-alias-cell")]
 impl<T: BeginPinnedInit> BeginPinnedInit for unsafe_alias_cell::UnsafeAliasCell<T> {
-    type OngoingInit<'init> = () where Self: 'init;
+    type OngoingInit<'init>
+        = T::OngoingInit<'init>
+    where
+        Self: 'init;
 
-    unsafe fn __begin_init<'init>(self: Pin<&'init mut Self>) -> Self::OngoingInit<'init>
+    #[inline]
+    unsafe fn __begin_init<'init>(
+        this: *mut Self,
+        flags: &'init Self::Flags,
+    ) -> Self::OngoingInit<'init>
     where
         Self: 'init,
     {
-        todo!()
+        unsafe {
+            // SAFETY: the contents of an `UnsafeAliasCell` are structurally
+            // pinned. The cell has no flags of its own, so the caller upholds
+            // the requirements of `__begin_init` for the contents.
+            T::__begin_init(unsafe_alias_cell::UnsafeAliasCell::raw_get(this), flags)
+        }
+    }
+}
+
+#[cfg(feature = "unsafe-alias-cell")]
+unsafe impl<T: private::PartialInit> private::PartialInit
+    for unsafe_alias_cell::UnsafeAliasCell<T>
+{
+    type Flags = T::Flags;
+
+    #[inline]
+    fn __new_flags() -> Self::Flags {
+        T::__new_flags()
+    }
+
+    #[inline]
+    unsafe fn __is_init(this: *const Self, flags: &Self::Flags) -> bool {
+        unsafe {
+            // SAFETY: the caller guarantees that `this` is valid.
+            T::__is_init(unsafe_alias_cell::UnsafeAliasCell::raw_get(this), flags)
+        }
+    }
+
+    #[inline]
+    unsafe fn __is_uninit(this: *const Self, flags: &Self::Flags) -> bool {
+        unsafe {
+            // SAFETY: the caller guarantees that `this` is valid.
+            T::__is_uninit(unsafe_alias_cell::UnsafeAliasCell::raw_get(this), flags)
+        }
+    }
+
+    #[inline]
+    fn __set_init(flags: &Self::Flags) {
+        T::__set_init(flags)
+    }
+
+    #[inline]
+    unsafe fn __drop_partial(this: *mut Self, flags: &Self::Flags) {
+        unsafe {
+            // SAFETY: `UnsafeAliasCell` does not implement `Drop`, so dropping the
+            // inner value is the same as dropping `this`.
+            T::__drop_partial(unsafe_alias_cell::UnsafeAliasCell::raw_get(this), flags)
+        }
     }
 }
 
@@ -328,16 +830,112 @@ unsafe impl<T: private::AsUninit> private::AsUninit for unsafe_alias_cell::Unsaf
     type Uninit = unsafe_alias_cell::UnsafeAliasCell<T::Uninit>;
 }
 
+/// Facilitates deinitializing a pinned value in place, turning it back into its
+/// uninitialized form `Self`, so its memory can be initialized again.
+/// Use [`SafePinnedInit::deinit`] to deinitialize the value behind a pinned
+/// pointer.
+///
+/// The [`pinned_init`] and [`manual_init`] proc macro attributes implement this
+/// trait for pinned structs, when you pass `deinit` to them (e.g.
+/// `#[manual_init(pinned, deinit)]`). All fields marked with `#[init]` need to
+/// support being deinitialized. `deinit` cannot be combined with
+/// `pin_project(PinnedDrop)`, because the `PinnedDrop` implementation would
+/// not run.
+///
+/// Only the fields marked with `#[init]` are dropped, the other fields are
+/// left untouched and reused by the next initialization:
+/// ```rust
+/// # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+/// use core::{mem::MaybeUninit, pin::Pin};
+/// use pinned_init::prelude::*;
+/// use std::rc::Rc;
+///
+/// #[manual_init(pinned, deinit)]
+/// pub struct Conn {
+///     #[init]
+///     #[uninit = MaybeUninit::<Rc<()>>]
+///     peer: Rc<()>,
+///     buf: Vec<u8>,
+/// }
+///
+/// impl PinnedInit for ConnUninit {
+///     type Initialized = Conn;
+///     type Param = Rc<()>;
+///
+///     fn init_raw(this: NeedsPinnedInit<Self>, peer: Rc<()>) {
+///         let ConnOngoingInit { peer: slot, .. } = this.begin_init();
+///         slot.init(peer);
+///     }
+/// }
+///
+/// let peer = Rc::new(());
+/// let uninit = ConnUninit { peer: MaybeUninit::uninit(), buf: Vec::with_capacity(1024) };
+/// let conn: Pin<Box<Conn>> = Box::pin(uninit).init_with(peer.clone());
+/// assert_eq!(Rc::strong_count(&peer), 2);
+/// let uninit: Pin<Box<ConnUninit>> = conn.deinit();
+/// assert_eq!(Rc::strong_count(&peer), 1);
+/// assert_eq!(uninit.buf.capacity(), 1024);
+/// let conn: Pin<Box<Conn>> = uninit.init_with(peer.clone());
+/// assert_eq!(Rc::strong_count(&conn.peer), 2);
+/// ```
+///
+/// # Safety
+///
+/// [`Self::deinit_raw`] must leave a valid value of `Self` behind, that does
+/// not own any of the resources of the value of `T`, that were dropped.
+pub unsafe trait PinnedDeinit<T>: TransmuteInto<T> {
+    /// Drops the initialized parts of the value at `this` in place. Afterwards
+    /// the value at `this` is a valid `Self`.
+    ///
+    /// When this function panics, the value at `this` needs to be leaked.
+    ///
+    /// # Safety
+    ///
+    /// - the caller needs to have unique access to the valid value at `this`.
+    /// - the caller needs to change the type of all pointers to the value to
+    ///   `Self`.
+    unsafe fn deinit_raw(this: *mut T);
+}
+
+// SAFETY: dropping the value leaves uninitialized memory behind, which is a
+// valid `MaybeUninit<T>`.
+unsafe impl<T> PinnedDeinit<T> for MaybeUninit<T> {
+    #[inline]
+    unsafe fn deinit_raw(this: *mut T) {
+        unsafe {
+            // SAFETY: the caller guarantees that the value is valid and that it
+            // is not used as a `T` again.
+            core::ptr::drop_in_place(this)
+        }
+    }
+}
+
+#[cfg(feature = "unsafe-alias-cell")]
+// SAFETY: the contents of an `UnsafeAliasCell` are deinitialized in place.
+unsafe impl<U: PinnedDeinit<T>, T> PinnedDeinit<unsafe_alias_cell::UnsafeAliasCell<T>>
+    for unsafe_alias_cell::UnsafeAliasCell<U>
+{
+    #[inline]
+    unsafe fn deinit_raw(this: *mut unsafe_alias_cell::UnsafeAliasCell<T>) {
+        unsafe {
+            // SAFETY: the caller upholds the requirements for the contents.
+            U::deinit_raw(unsafe_alias_cell::UnsafeAliasCell::raw_get(this))
+        }
+    }
+}
+
 /// Facilitates pinned initialization.
 /// Before you implement this trait manually, look at the [`pinned_init`] proc
 /// macro attribute, it can be used to implement this trait in a safe and sound
 /// fashion in many cases.
 ///
 /// You will need to implement this trait yourself, if your struct contains any
-/// fields with the [`static_uninit::StaticUninit`] type. When implementing this
+/// fields with the [`StaticUninit`] type. When implementing this
 /// trait manually, use the [`manual_init`] proc macro attribute to implement
 /// [`BeginPinnedInit`] for your struct, as implementing that trait is not supposed to
 /// be done manually.
+///
+/// [`StaticUninit`]: static_uninit::StaticUninit
 pub trait Init: TransmuteInto<Self::Initialized> + BeginInit {
     /// The initialized version of `Self`. `Self` can be transmuted via
     /// [`TransmuteInto`] into this type.
@@ -351,73 +949,751 @@ pub trait Init: TransmuteInto<Self::Initialized> + BeginInit {
     fn init_raw(this: NeedsInit<Self>, param: Self::Param);
 }
 
+/// Facilitates fallible initialization.
+///
+/// This is the fallible counterpart of [`Init`], use it when initializing `Self`
+/// can fail. Implementing this trait works the same way as implementing [`Init`],
+/// use the [`manual_init`] proc macro attribute to implement [`BeginInit`] for your
+/// struct.
+///
+/// When [`Self::try_init_raw`] returns an error, you do not need to clean up: all
+/// fields that were already initialized are dropped in reverse order, the remaining
+/// fields are dropped in their uninitialized form.
+pub trait TryInit: TransmuteInto<Self::Initialized> + BeginInit {
+    /// The initialized version of `Self`. `Self` can be transmuted via
+    /// [`TransmuteInto`] into this type.
+    type Initialized;
+    /// An optional Parameter used to initialize `Self`.
+    /// When you do not need it, set to `()`
+    type Param;
+    /// The error that can occur while initializing `Self`.
+    type Error;
+
+    /// Initialize the value behind the given pointer with the given parameter, this pointer ensures,
+    /// that `Self` really will be initialized, when `Ok(())` is returned.
+    fn try_init_raw(this: NeedsInit<Self>, param: Self::Param) -> Result<(), Self::Error>;
+}
+
 // used to prevent accidental/mailicious implementations of `SafePinnedInit`
 mod sealed {
     use super::*;
 
-    pub trait Sealed<T: PinnedInit> {}
+    pub trait Sealed<T> {}
 
-    impl<T: PinnedInit, P: OwnedUniquePtr<T>> Sealed<T> for Pin<P> {}
+    impl<T, P: OwnedUniquePtr<T>> Sealed<T> for Pin<P> {}
+
+    pub trait SealedReinit<T> {}
+
+    impl<T> SealedReinit<T> for Pin<&mut T> {}
 }
 
 /// Sealed trait to facilitate safe initialization of the types supported by
 /// this crate.
 ///
 /// Use this traits [`Self::init`] method to initialize the T contained in `self`.
-/// This trait is implemented only for [`Pin<P>`] `where P:` [`OwnedUniquePtr<T>`] `, T:` [`PinnedInit`].
-pub trait SafePinnedInit<T: PinnedInit>: sealed::Sealed<T> + Sized {
-    /// The type that represents the initialized version of `self`.
-    type Initialized;
+/// This trait is implemented only for [`Pin<P>`] `where P:` [`OwnedUniquePtr<T>`].
+/// `T` needs to implement [`PinnedInit`] or [`TryPinnedInit`], depending on the
+/// method used.
+pub trait SafePinnedInit<T>: sealed::Sealed<T> + Sized {
+    /// The pinned pointer type of `self`, but pointing to a `U`.
+    type Pinned<U>;
 
-    /// Initialize the contents of `self`.
+    /// Initialize the contents of `self` using the default parameter, see
+    /// [`DefaultParam`].
     #[inline]
-    fn init(self) -> Self::Initialized
+    fn init(self) -> Self::Pinned<T::Initialized>
     where
-        (): SimpleInto<T::Param>,
+        T: PinnedInit,
+        T::Param: DefaultParam,
     {
-        self.init_with(SimpleInto::into(()))
+        self.init_with(DefaultParam::default_param())
     }
 
     /// Initialize the contents of `self`.
-    fn init_with(self, param: T::Param) -> Self::Initialized;
+    ///
+    /// When the initializer panics, the partially initialized value is dropped
+    /// and the memory is freed before the panic continues to unwind.
+    fn init_with(self, param: T::Param) -> Self::Pinned<T::Initialized>
+    where
+        T: PinnedInit;
+
+    /// Try to initialize the contents of `self`.
+    ///
+    /// When the initialization fails (or panics), the partially initialized
+    /// value is dropped, the memory is freed and the error is returned (or the
+    /// panic continues to unwind).
+    fn try_init_with(self, param: T::Param) -> Result<Self::Pinned<T::Initialized>, T::Error>
+    where
+        T: TryPinnedInit;
+
+    /// Deinitialize the contents of `self` in place, turning them back into
+    /// their uninitialized form, see [`PinnedDeinit`]. The returned pointer
+    /// reuses the memory of `self` and can be initialized again.
+    ///
+    /// When deinitializing panics, the value and its memory are leaked before
+    /// the panic continues to unwind. The parts of the value, that were not yet
+    /// dropped, might still be pinned and thus their memory must not be reused:
+    /// ```rust
+    /// # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+    /// use core::{mem::MaybeUninit, pin::Pin};
+    /// use pinned_init::prelude::*;
+    /// use std::{panic, rc::Rc};
+    ///
+    /// pub struct Bomb;
+    ///
+    /// impl Drop for Bomb {
+    ///     fn drop(&mut self) {
+    ///         panic!("boom");
+    ///     }
+    /// }
+    ///
+    /// #[manual_init(pinned, deinit)]
+    /// pub struct Conn {
+    ///     #[init]
+    ///     #[uninit = MaybeUninit::<Bomb>]
+    ///     bomb: Bomb,
+    ///     #[pin]
+    ///     peer: Rc<()>,
+    /// }
+    ///
+    /// impl PinnedInit for ConnUninit {
+    ///     type Initialized = Conn;
+    ///     type Param = ();
+    ///
+    ///     fn init_raw(this: NeedsPinnedInit<Self>, _: ()) {
+    ///         let ConnOngoingInit { bomb, .. } = this.begin_init();
+    ///         bomb.init(Bomb);
+    ///     }
+    /// }
+    ///
+    /// let peer = Rc::new(());
+    /// let uninit = ConnUninit { bomb: MaybeUninit::uninit(), peer: peer.clone() };
+    /// let conn: Pin<Box<Conn>> = Box::pin(uninit).init();
+    /// let res = panic::catch_unwind(panic::AssertUnwindSafe(|| conn.deinit()));
+    /// assert!(res.is_err());
+    /// // `peer` was leaked together with the memory of `conn`.
+    /// assert_eq!(Rc::strong_count(&peer), 2);
+    /// ```
+    fn deinit(self) -> Self::Pinned<T::Uninit>
+    where
+        T: AsUninit + TransmuteInto<T::Uninit>,
+        T::Uninit: PinnedDeinit<T>;
 }
+
+/// Sealed trait to replace a pinned value in place with a newly initialized
+/// one.
+///
+/// This trait is implemented only for [`Pin<&mut T>`], it is useful when the
+/// value is part of a larger pinned value and thus cannot be reallocated:
+/// ```rust
+/// # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+/// use core::{mem::MaybeUninit, pin::Pin};
+/// use pinned_init::prelude::*;
+///
+/// #[manual_init(pinned)]
+/// pub struct Timer {
+///     #[init]
+///     deadline: u64,
+/// }
+///
+/// impl PinnedInit for TimerUninit {
+///     type Initialized = Timer;
+///     type Param = u64;
 ///
-pub trait SimpleInto<T> {
+///     fn init_raw(this: NeedsPinnedInit<Self>, deadline: u64) {
+///         let TimerOngoingInit { deadline: slot } = this.begin_init();
+///         slot.init(deadline);
+///     }
+/// }
+///
+/// #[pinned_init]
+/// pub struct Conn {
+///     #[init]
+///     timer: Timer,
+///     id: usize,
+/// }
+///
+/// impl Conn {
+///     pub fn reset_timer(self: Pin<&mut Self>, deadline: u64) {
+///         let timer = self.project().timer;
+///         timer.reinit(TimerUninit { deadline: MaybeUninit::uninit() }, deadline);
+///     }
+/// }
+///
+/// let uninit = ConnUninit { timer: TimerUninit { deadline: MaybeUninit::uninit() }, id: 0 };
+/// let mut conn: Pin<Box<Conn>> = Box::pin(uninit).init_with(ConnInitParams { timer: 10 });
+/// conn.as_mut().reset_timer(20);
+/// assert_eq!(conn.timer.deadline, 20);
+/// ```
+pub trait PinnedReinit<T>: sealed::SealedReinit<T> + Sized {
+    /// Drops the value behind `self` in place, moves `uninit` into its memory
+    /// and initializes it using `param`.
+    ///
+    /// # Aborts
     ///
-    fn into(self) -> T;
+    /// The memory behind `self` is owned by someone else, who will drop it
+    /// again. So when dropping the old value or initializing the new one
+    /// panics, there is no valid value left and the process is aborted.
+    fn reinit<U>(self, uninit: U, param: U::Param) -> Self
+    where
+        U: PinnedInit<Initialized = T>;
 }
 
-macro_rules! simple_into {
-    ($([$($what:tt)*])*) => {
-        $(
-            impl SimpleInto<$($what)*> for () {
-                fn into(self) -> $($what)* {
-                    $($what)*
-                }
+impl<T> PinnedReinit<T> for Pin<&mut T> {
+    fn reinit<U>(self, uninit: U, param: U::Param) -> Self
+    where
+        U: PinnedInit<Initialized = T>,
+    {
+        let ptr = unsafe {
+            // SAFETY: we never move the value behind `self`.
+            self.get_unchecked_mut() as *mut T
+        };
+        // between dropping the old value and initializing the new one, the
+        // memory does not hold a valid `T`, so we must not unwind.
+        let guard = AbortOnUnwind;
+        let res = unsafe {
+            // SAFETY: we have unique access to the value, it is not used again
+            // before it is initialized again.
+            core::ptr::drop_in_place(ptr);
+            let ptr = ptr as *mut U;
+            // SAFETY: `U` and `T` have the same layout, because
+            // `U: TransmuteInto<T>`.
+            ptr.write(uninit);
+            // SAFETY: the value is pinned and we change the type of the
+            // pointer below.
+            init_in_place(Pin::new_unchecked(&mut *ptr), |this| {
+                U::init_raw(this, param);
+                Ok::<(), Infallible>(())
+            })
+        };
+        match res {
+            Ok(()) => {}
+            Err(e) => match e {},
+        }
+        mem::forget(guard);
+        unsafe {
+            // SAFETY: the value has been fully initialized and is still pinned.
+            Pin::new_unchecked(&mut *ptr)
+        }
+    }
+}
+
+/// Aborts the process, when it is dropped while unwinding.
+struct AbortOnUnwind;
+
+impl Drop for AbortOnUnwind {
+    fn drop(&mut self) {
+        // panicking while unwinding aborts the process, this also works
+        // without `std`.
+        panic!("A pinned value could not be replaced, the process is aborted!");
+    }
+}
+
+/// A parameter with a canonical default value, used to initialize values
+/// without explicitly supplying a parameter (e.g. via [`SafePinnedInit::init`]
+/// or [`stack_init!`]).
+///
+/// This trait is implemented for `()`, tuples of up to twelve elements and
+/// arrays with elements implementing [`DefaultParam`]. The
+/// `{your-struct-name}InitParams` struct generated by [`pinned_init`]
+/// implements it, when all of its members do. For your own parameter types use
+/// `#[derive(DefaultParam)]`:
+/// ```rust
+/// # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+/// use core::{mem::MaybeUninit, pin::Pin};
+/// use pinned_init::prelude::*;
+///
+/// #[derive(DefaultParam)]
+/// pub struct QueueParams {
+///     reserved: (),
+///     limits: ((), ()),
+/// }
+///
+/// #[manual_init(pinned)]
+/// pub struct Queue {
+///     #[init]
+///     len: usize,
+/// }
+///
+/// impl PinnedInit for QueueUninit {
+///     type Initialized = Queue;
+///     type Param = QueueParams;
+///
+///     fn init_raw(this: NeedsPinnedInit<Self>, _: QueueParams) {
+///         let QueueOngoingInit { len } = this.begin_init();
+///         len.init(0);
+///     }
+/// }
+///
+/// let queue: Pin<Box<Queue>> = Box::pin(QueueUninit { len: MaybeUninit::uninit() }).init();
+/// assert_eq!(queue.len, 0);
+/// ```
+///
+/// [`stack_init!`]: crate::stack_init
+pub trait DefaultParam {
+    /// Returns the default parameter.
+    fn default_param() -> Self;
+}
+
+/// Derives [`DefaultParam`] for a struct, all of its fields need to implement
+/// [`DefaultParam`]. Like `#[derive(Default)]`, every type parameter of the
+/// struct is required to implement [`DefaultParam`] as well.
+pub use pinned_init_macro::DefaultParam;
+
+/// A member of the `{your-struct-name}InitParams` struct generated by
+/// [`pinned_init`] that was not set via its builder. When the builder is built,
+/// it is replaced by the [`DefaultParam`] of the parameter, so members whose
+/// parameter is e.g. `()` do not need to be set.
+#[derive(Clone, Copy, Debug)]
+pub struct Unset;
+
+macro_rules! tuple_default_param {
+    ($($t:ident),*) => {
+        impl<$($t: DefaultParam),*> DefaultParam for ($($t,)*) {
+            #[inline]
+            fn default_param() -> Self {
+                ($($t::default_param(),)*)
             }
-        )*
+        }
+    };
+}
+
+impl DefaultParam for () {
+    #[inline]
+    fn default_param() -> Self {}
+}
+
+tuple_default_param!(A);
+tuple_default_param!(A, B);
+tuple_default_param!(A, B, C);
+tuple_default_param!(A, B, C, D);
+tuple_default_param!(A, B, C, D, E);
+tuple_default_param!(A, B, C, D, E, F);
+tuple_default_param!(A, B, C, D, E, F, G);
+tuple_default_param!(A, B, C, D, E, F, G, H);
+tuple_default_param!(A, B, C, D, E, F, G, H, I);
+tuple_default_param!(A, B, C, D, E, F, G, H, I, J);
+tuple_default_param!(A, B, C, D, E, F, G, H, I, J, K);
+tuple_default_param!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+impl<T, P: OwnedUniquePtr<T>> SafePinnedInit<T> for Pin<P> {
+    type Pinned<U> = Pin<P::Ptr<U>>;
+
+    #[inline]
+    fn init_with(self, param: T::Param) -> Self::Pinned<T::Initialized>
+    where
+        T: PinnedInit,
+    {
+        let res = init_pinned(self, |this| {
+            T::init_raw(this, param);
+            Ok::<(), Infallible>(())
+        });
+        match res {
+            Ok(this) => this,
+            Err(e) => match e {},
+        }
+    }
+
+    #[inline]
+    fn try_init_with(self, param: T::Param) -> Result<Self::Pinned<T::Initialized>, T::Error>
+    where
+        T: TryPinnedInit,
+    {
+        init_pinned(self, |this| T::try_init_raw(this, param))
+    }
+
+    fn deinit(self) -> Self::Pinned<T::Uninit>
+    where
+        T: AsUninit + TransmuteInto<T::Uninit>,
+        T::Uninit: PinnedDeinit<T>,
+    {
+        // when deinitializing panics, the fields that were not yet dropped may
+        // still be pinned, so we must leak the memory.
+        let mut this = ManuallyDrop::new(self);
+        unsafe {
+            // SAFETY: `this` implements `OwnedUniquePtr`, thus giving us unique
+            // access to the data behind it. We never move the value and change
+            // the type of the pointee below.
+            T::Uninit::deinit_raw(this.as_mut().get_unchecked_mut())
+        }
+        unsafe {
+            // SAFETY: `this` is never used again and the value is now a valid
+            // `T::Uninit`.
+            P::transmute_pointee_pinned(ManuallyDrop::take(&mut this))
+        }
     }
 }
-simple_into! {
-    [()]
-    [((),)]
-    [(((),),)]
+
+/// Initializes the value behind `this` using `init` and transmutes it to `U`.
+///
+/// When `init` fails or panics, the partially initialized value is dropped and
+/// the memory behind `this` is freed.
+fn init_pinned<T, U, P, E>(
+    this: Pin<P>,
+    init: impl FnOnce(NeedsPinnedInit<'_, T>) -> Result<(), E>,
+) -> Result<Pin<P::Ptr<U>>, E>
+where
+    T: BeginPinnedInit + TransmuteInto<U>,
+    P: OwnedUniquePtr<T>,
+{
+    // when the initialization fails, the value will have been dropped in place, so
+    // we must only free the memory.
+    let mut guard = FreeOnDrop::<T, P> {
+        ptr: ManuallyDrop::new(this),
+        _t: PhantomData,
+    };
+    unsafe {
+        // SAFETY: `guard.ptr` implements `OwnedUniquePtr`, thus giving us unique
+        // access to the data behind it. On success we transmute the pointee
+        // below, on failure the value is dropped and we only free the memory.
+        init_in_place(guard.ptr.as_mut(), init)?
+    };
+    let mut guard = ManuallyDrop::new(guard);
+    Ok(unsafe {
+        // SAFETY: `guard` is never used again and the value has been fully
+        // initialized.
+        P::transmute_pointee_pinned(ManuallyDrop::take(&mut guard.ptr))
+    })
 }
 
-impl<T: PinnedInit, P: OwnedUniquePtr<T>> SafePinnedInit<T> for Pin<P> {
-    type Initialized = Pin<P::Ptr<T::Initialized>>;
+/// A value that has already been dropped (or is leaked), only used to free its
+/// memory. This type is private, so the impl below cannot be used outside of
+/// this crate.
+#[repr(transparent)]
+struct Forgotten<T>(MaybeUninit<T>);
 
+// SAFETY: `Forgotten<T>` is a `repr(transparent)` wrapper of `MaybeUninit<T>`,
+// which has the same layout as `T` and has no invariants.
+#[doc(hidden)]
+unsafe impl<T> TransmuteInto<Forgotten<T>> for T {
     #[inline]
-    fn init_with(mut self, param: T::Param) -> Self::Initialized {
+    unsafe fn transmute_ptr(this: *const Self) -> *const Forgotten<T> {
+        this as *const Forgotten<T>
+    }
+}
+
+/// Frees the memory behind `ptr` without dropping the value.
+struct FreeOnDrop<T, P: OwnedUniquePtr<T>> {
+    ptr: ManuallyDrop<Pin<P>>,
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<T, P: OwnedUniquePtr<T>> Drop for FreeOnDrop<T, P> {
+    fn drop(&mut self) {
+        drop(unsafe {
+            // SAFETY: the value has already been dropped, so it is only a
+            // `Forgotten<T>` now, dropping that only frees the memory. `ptr` is
+            // not used again.
+            P::transmute_pointee_pinned::<Forgotten<T>>(ManuallyDrop::take(&mut self.ptr))
+        });
+    }
+}
+
+/// Drops a partially initialized value, when the initialization fails.
+struct DropPartial<'a, T: PartialInit> {
+    ptr: *mut T,
+    flags: &'a T::Flags,
+}
+
+impl<'a, T: PartialInit> Drop for DropPartial<'a, T> {
+    fn drop(&mut self) {
         unsafe {
-            // SAFETY: `self` implements `OwnedUniquePtr`, thus giving us unique
-            // access to the data behind `self`. Because we call `T::init_raw`
-            // and `P::transmute_pointee_pinned` below, the contract of
-            // `NeedsPinnedInit::new_unchecked` is fullfilled (all pointers to
-            // the data are aware of the new_unchecked call).
-            let this = NeedsPinnedInit::new_unchecked(self.as_mut());
-            T::init_raw(this, param);
-            P::transmute_pointee_pinned(self)
+            // SAFETY: `flags` track the initialization of the value at `ptr`.
+            T::__drop_partial(self.ptr, self.flags)
+        }
+    }
+}
+
+/// Initializes the value behind `this` in place using `init`.
+///
+/// Returns `Ok(())` when the value is fully initialized and can be transmuted
+/// to its initialized form. When `init` returns an error or panics, the
+/// partially initialized value is dropped in place and the error is returned
+/// (or the panic continues).
+///
+/// # Panics
+///
+/// Panics when `init` returns `Ok(())` without initializing all fields, the
+/// partially initialized value is dropped in place before panicking.
+///
+/// # Safety
+///
+/// - the caller needs to have unique access to the value behind `this`.
+/// - when `Ok(())` is returned, the caller needs to change the type of all
+///   pointers to the value to its initialized form.
+/// - when an error is returned (or this function panics), the value behind
+///   `this` must not be used or dropped again.
+unsafe fn init_in_place<T, E>(
+    this: Pin<&mut T>,
+    init: impl FnOnce(NeedsPinnedInit<'_, T>) -> Result<(), E>,
+) -> Result<(), E>
+where
+    T: BeginPinnedInit,
+{
+    let flags = T::__new_flags();
+    let ptr = unsafe {
+        // SAFETY: we never move the value behind `this`.
+        this.get_unchecked_mut() as *mut T
+    };
+    // when `init` panics, the guard drops the partially initialized value.
+    let guard = DropPartial { ptr, flags: &flags };
+    init(unsafe {
+        // SAFETY: the caller guarantees unique access and that the type of the
+        // value changes when we return `Ok(())`. When the initialization fails,
+        // the value is dropped according to `flags`.
+        NeedsPinnedInit::from_raw(ptr, &flags)
+    })?;
+    #[cfg(not(pinned_init_unsafe_no_enforce_init))]
+    if !unsafe {
+        // SAFETY: `ptr` is valid and `flags` track its initialization.
+        T::__is_init(ptr, &flags)
+    } {
+        drop(guard);
+        panic!(
+            "The value at {:p} was not fully initialized, but its initializer returned successfully!",
+            ptr
+        );
+    }
+    mem::forget(guard);
+    Ok(())
+}
+
+/// Allocates a slice of `len` elements, moves `uninit(i)` into the `i`-th
+/// element, initializes the slice using `init` and transmutes it to `[U]`.
+///
+/// When `uninit` or `init` fail or panic, the written and initialized elements
+/// are dropped and the memory is freed.
+#[cfg(feature = "alloc")]
+fn init_slice_pinned<T, U, P, E>(
+    len: usize,
+    mut uninit: impl FnMut(usize) -> T,
+    init: impl FnOnce(NeedsPinnedInitSlice<'_, T>) -> Result<(), E>,
+) -> Result<Pin<P::Ptr<[U]>>, E>
+where
+    T: PartialInit + TransmuteInto<U>,
+    P: AllocUninitSlice<T>,
+{
+    let mut this = P::alloc_uninit_slice(len);
+    let elems = unsafe {
+        // SAFETY: we never move the elements.
+        this.as_mut().get_unchecked_mut()
+    };
+    // when `uninit` panics, the guard drops the already written elements.
+    let mut guard = DropWrittenSlice {
+        ptr: elems.as_mut_ptr() as *mut T,
+        len: 0,
+    };
+    for (i, elem) in elems.iter_mut().enumerate() {
+        elem.write(uninit(i));
+        guard.len = i + 1;
+    }
+    mem::forget(guard);
+    // when the initialization fails, the elements will have been dropped in
+    // place, so we must only free the memory.
+    let mut guard = FreeSliceOnDrop::<T, P> {
+        ptr: ManuallyDrop::new(unsafe {
+            // SAFETY: all elements have been written.
+            P::assume_init_slice_pinned(this)
+        }),
+        _t: PhantomData,
+    };
+    unsafe {
+        // SAFETY: `guard.ptr` implements `OwnedUniquePtr`, thus giving us unique
+        // access to the slice behind it. On success we transmute the elements
+        // below, on failure they are dropped and we only free the memory.
+        init_slice_in_place(guard.ptr.as_mut(), init)?
+    };
+    let mut guard = ManuallyDrop::new(guard);
+    Ok(unsafe {
+        // SAFETY: `guard` is never used again and all elements have been fully
+        // initialized.
+        P::transmute_slice_pinned(ManuallyDrop::take(&mut guard.ptr))
+    })
+}
+
+/// Frees the memory behind `ptr` without dropping the elements.
+#[cfg(feature = "alloc")]
+struct FreeSliceOnDrop<T, P: AllocUninitSlice<T>> {
+    ptr: ManuallyDrop<Pin<P>>,
+    _t: PhantomData<fn() -> T>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T, P: AllocUninitSlice<T>> Drop for FreeSliceOnDrop<T, P> {
+    fn drop(&mut self) {
+        drop(unsafe {
+            // SAFETY: the elements have already been dropped, so they are only
+            // `Forgotten<T>`s now, dropping those only frees the memory. `ptr`
+            // is not used again.
+            P::transmute_slice_pinned::<Forgotten<T>>(ManuallyDrop::take(&mut self.ptr))
+        });
+    }
+}
+
+/// Drops the first `len` elements at `ptr`, when writing the elements of a
+/// slice fails.
+#[cfg(feature = "alloc")]
+struct DropWrittenSlice<T> {
+    ptr: *mut T,
+    len: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Drop for DropWrittenSlice<T> {
+    fn drop(&mut self) {
+        unsafe {
+            // SAFETY: the first `len` elements have been written.
+            core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(self.ptr, self.len))
+        }
+    }
+}
+
+/// Drops the elements of a partially initialized slice in reverse order, when
+/// the initialization fails.
+#[cfg(feature = "alloc")]
+struct DropPartialSlice<'a, T: PartialInit> {
+    ptr: *mut T,
+    flags: &'a [T::Flags],
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: PartialInit> Drop for DropPartialSlice<'a, T> {
+    fn drop(&mut self) {
+        for (i, flags) in self.flags.iter().enumerate().rev() {
+            unsafe {
+                // SAFETY: `flags` track the initialization of the elements at
+                // `ptr`.
+                T::__drop_partial(self.ptr.add(i), flags)
+            }
         }
     }
 }
+
+/// Initializes the elements of the slice behind `this` in place using `init`.
+///
+/// This is the slice counterpart of [`init_in_place`].
+///
+/// # Panics
+///
+/// Panics when `init` returns `Ok(())` without initializing all elements, the
+/// partially initialized elements are dropped in place before panicking.
+///
+/// # Safety
+///
+/// The same requirements as for [`init_in_place`] apply to every element.
+#[cfg(feature = "alloc")]
+unsafe fn init_slice_in_place<T, E>(
+    this: Pin<&mut [T]>,
+    init: impl FnOnce(NeedsPinnedInitSlice<'_, T>) -> Result<(), E>,
+) -> Result<(), E>
+where
+    T: PartialInit,
+{
+    let flags = (0..this.len())
+        .map(|_| T::__new_flags())
+        .collect::<alloc::vec::Vec<_>>();
+    let ptr = unsafe {
+        // SAFETY: we never move the elements behind `this`.
+        this.get_unchecked_mut() as *mut [T]
+    };
+    // when `init` panics, the guard drops the partially initialized elements.
+    let guard = DropPartialSlice {
+        ptr: ptr as *mut T,
+        flags: &flags,
+    };
+    init(unsafe {
+        // SAFETY: the caller guarantees unique access and that the type of the
+        // elements changes when we return `Ok(())`. When the initialization
+        // fails, the elements are dropped according to `flags`.
+        NeedsPinnedInitSlice::from_raw(ptr as *mut T, &flags)
+    })?;
+    #[cfg(not(pinned_init_unsafe_no_enforce_init))]
+    if !flags.iter().enumerate().all(|(i, flags)| unsafe {
+        // SAFETY: `i` is in bounds and `flags` track the initialization of the
+        // element.
+        T::__is_init((ptr as *const T).add(i), flags)
+    }) {
+        drop(guard);
+        panic!(
+            "The slice at {:p} was not fully initialized, but its initializer returned successfully!",
+            ptr
+        );
+    }
+    mem::forget(guard);
+    Ok(())
+}
+
+/// Drops the fields of a value that have been written, when writing fails.
+struct DropWritten<'a, T: WriteUninit> {
+    ptr: *mut T,
+    flags: &'a T::WriteFlags,
+}
+
+impl<'a, T: WriteUninit> Drop for DropWritten<'a, T> {
+    fn drop(&mut self) {
+        unsafe {
+            // SAFETY: `flags` track the writes to the value at `ptr`.
+            T::__drop_written(self.ptr, self.flags)
+        }
+    }
+}
+
+/// Writes the fields of the value at `this` using `write`.
+///
+/// # Panics
+///
+/// Panics when `write` returns without writing all fields, the written fields
+/// are dropped before panicking.
+///
+/// # Safety
+///
+/// - `this` needs to be valid for writes and the caller needs to have unique
+///   access to it.
+/// - when this function panics, the value at `this` must not be used or
+///   dropped.
+unsafe fn write_in_place<T: WriteUninit>(this: *mut T, write: impl FnOnce(T::Slots<'_>)) {
+    let flags = T::__new_write_flags();
+    // when `write` panics, the guard drops the written fields.
+    let guard = DropWritten {
+        ptr: this,
+        flags: &flags,
+    };
+    write(unsafe {
+        // SAFETY: the caller guarantees that `this` is valid and `flags` are new.
+        T::__slots(this, &flags)
+    });
+    if !T::__is_written(&flags) {
+        drop(guard);
+        panic!(
+            "The value at {:p} was not fully written, but its writer returned successfully!",
+            this
+        );
+    }
+    mem::forget(guard);
+}
+
+/// Allocates a new `T` behind a `P`, writes it using `write` and then
+/// initializes it using `init`.
+///
+/// When any of the steps fails or panics, all written and initialized values
+/// are dropped and the memory is freed.
+fn write_init_pinned<T, U, P, E>(
+    write: impl FnOnce(T::Slots<'_>),
+    init: impl FnOnce(NeedsPinnedInit<'_, T>) -> Result<(), E>,
+) -> Result<Pin<P::Ptr<U>>, E>
+where
+    T: WriteUninit + BeginPinnedInit + TransmuteInto<U>,
+    P: AllocUninit<T>,
+{
+    let mut uninit = P::alloc_uninit();
+    unsafe {
+        // SAFETY: we never move the value out of the allocation. When writing
+        // fails, `uninit` only frees the memory.
+        write_in_place(uninit.as_mut().get_unchecked_mut().as_mut_ptr(), write);
+    }
+    let this = unsafe {
+        // SAFETY: all fields have been written.
+        P::assume_init_pinned(uninit)
+    };
+    init_pinned(this, init)
+}
@@ -1,70 +1,185 @@
 //! Custom pointer types used to ensure that initialization was done completly.
-//! The pointer types provided by this module [`NeedsPinnedInit<'init, T>`] and
-//! [`NeedsInit<'init, T>`] will
-//! - panic when debug assertions are turned on
-//! - produce link time erros when debug assertions are turned off
-//! when they determine, that a value has not been initialized.
+//! The pointer types provided by this module [`NeedsPinnedInit<'init, T>`],
+//! [`NeedsPinnedInitSlice<'init, T>`] and [`NeedsInit<'init, T>`] record which
+//! values have been initialized.
 //!
-//! This catches simple errors when forgetting a variable.
+//! After the initializer of a value returns, this record is checked:
+//! - if the initializer succeeded, but a value has not been initialized, the
+//!   partially initialized value is dropped and a panic is raised.
+//! - if the initializer failed, the partially initialized value is dropped:
+//!   every part that was already initialized is dropped in its initialized form,
+//!   the remaining parts are dropped in their uninitialized form.
 //!
-//! The link time errors emitted by the linker will look overwhelming and are
-//! not very helpful (you might be able to interpret the LLVM symbol where the
-//! link error originated), instead they help you extend your tests or check your
-//! unused variable warnings with more scrutiny.
+//! - if the initializer panicked, the partially initialized value is dropped in
+//!   the same way, the memory of the value is freed and the panic continues to
+//!   unwind. This makes it safe to use [`std::panic::catch_unwind`] around an
+//!   initialization.
 //!
-//! <details><summary><b>Example Linker error</b></summary>
-//! ```text
-//! TODO ERROR
+//! This catches simple errors when forgetting a variable and allows
+//! initializers to fail without leaking or double dropping any values.
+//!
+//! ```rust,should_panic
+//! # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+//! use core::mem::MaybeUninit;
+//! use pinned_init::prelude::*;
+//!
+//! #[manual_init(pinned)]
+//! pub struct Pair {
+//!     #[init]
+//!     #[uninit = MaybeUninit::<u32>]
+//!     a: u32,
+//!     #[init]
+//!     #[uninit = MaybeUninit::<u32>]
+//!     b: u32,
+//! }
+//!
+//! impl PinnedInit for PairUninit {
+//!     type Initialized = Pair;
+//!     type Param = ();
+//!
+//!     fn init_raw(this: NeedsPinnedInit<Self>, _: ()) {
+//!         let PairOngoingInit { a, b: _ } = this.begin_init();
+//!         a.init(42);
+//!         // we forgot to initialize `b`...
+//!     }
+//! }
+//!
+//! let uninit = PairUninit {
+//!     a: MaybeUninit::uninit(),
+//!     b: MaybeUninit::uninit(),
+//! };
+//! // ...so this panics.
+//! let pair = Box::pin(uninit).init();
 //! ```
-//! TODO
+//!
+//! A panic inside of an initializer only drops the fields that were already
+//! initialized:
 //! ```rust
-//! TODO EXAMPLE CODE
-//! ```
-//! When running the debug build instead, we end up with the following panic,
-//! which is a lot more helpful at providing the relevant information:
-//! ```text
-//! TODO PANIC
+//! # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+//! use core::mem::MaybeUninit;
+//! use pinned_init::prelude::*;
+//! use std::{panic, sync::Arc};
+//!
+//! #[manual_init(pinned)]
+//! pub struct Pair {
+//!     #[init]
+//!     #[uninit = MaybeUninit::<Arc<u32>>]
+//!     a: Arc<u32>,
+//!     #[init]
+//!     #[uninit = MaybeUninit::<Arc<u32>>]
+//!     b: Arc<u32>,
+//! }
+//!
+//! impl PinnedInit for PairUninit {
+//!     type Initialized = Pair;
+//!     type Param = Arc<u32>;
+//!
+//!     fn init_raw(this: NeedsPinnedInit<Self>, value: Arc<u32>) {
+//!         let PairOngoingInit { a, b } = this.begin_init();
+//!         a.init(value.clone());
+//!         if *value == 0 {
+//!             panic!("cannot initialize `b`");
+//!         }
+//!         b.init(value);
+//!     }
+//! }
+//!
+//! let value = Arc::new(0);
+//! let res = panic::catch_unwind(|| {
+//!     let uninit = PairUninit {
+//!         a: MaybeUninit::uninit(),
+//!         b: MaybeUninit::uninit(),
+//!     };
+//!     Box::pin(uninit).init_with(value.clone())
+//! });
+//! assert!(res.is_err());
+//! // `a` has been dropped, `b` was never initialized.
+//! assert_eq!(Arc::strong_count(&value), 1);
 //! ```
-//! </details>
 //!
-//! # Behaviour rationale
+//! Initializing a value returns a reference to the initialized value, that
+//! stays valid for the rest of the initialization. This allows initializing
+//! values that depend on each other in sequence, without any `unsafe`:
+//! ```rust
+//! # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+//! use core::{mem::MaybeUninit, pin::Pin};
+//! use pinned_init::prelude::*;
 //!
-//! The correctness of the link error relies on the compiler to optimize the
-//! drop glue away. This is of course only possible, if optimizations are
-//! enabled.
-//! If you encounter such a link error and receive no runtime errors despite
-//! running all initializer functions and being very sure that you did not
-//! forget initializing a value, then feel free to report this issue at [my
-//! repo](https://github.com/y86-dev/pinned-init/).
+//! #[manual_init(pinned)]
+//! pub struct Counter {
+//!     #[init]
+//!     #[uninit = MaybeUninit::<u64>]
+//!     count: u64,
+//! }
 //!
-//! If in some rare cases the compiler is unable to determine that all instances
-//! of [`NeedsPinnedInit`] and [`NeedsInit`] are initialized, you are able to
-//! disable the static and dynamic initialization check provided by this module:
-//! pass the `pinned_init_unsafe_no_enforce_init` flag to rustc:
+//! impl PinnedInit for CounterUninit {
+//!     type Initialized = Counter;
+//!     type Param = u64;
+//!
+//!     fn init_raw(this: NeedsPinnedInit<Self>, start: u64) {
+//!         let CounterOngoingInit { count } = this.begin_init();
+//!         count.init(start);
+//!     }
+//! }
+//!
+//! #[manual_init(pinned)]
+//! pub struct Range {
+//!     #[init]
+//!     #[pin]
+//!     start: Counter,
+//!     #[init]
+//!     #[pin]
+//!     end: Counter,
+//! }
+//!
+//! impl PinnedInit for RangeUninit {
+//!     type Initialized = Range;
+//!     type Param = (u64, u64);
+//!
+//!     fn init_raw(this: NeedsPinnedInit<Self>, (start, len): (u64, u64)) {
+//!         let RangeOngoingInit { start: first, end } = this.begin_init();
+//!         let first: Pin<&mut Counter> = first.init_with(start);
+//!         end.init_with(first.count + len);
+//!     }
+//! }
+//!
+//! let uninit = RangeUninit {
+//!     start: CounterUninit { count: MaybeUninit::uninit() },
+//!     end: CounterUninit { count: MaybeUninit::uninit() },
+//! };
+//! let range = Box::pin(uninit).init_with((10, 5));
+//! assert_eq!((range.start.count, range.end.count), (10, 15));
+//! ```
+//!
+//! # Disabling the check
+//!
+//! If in some rare cases the cost of checking the initialization is too high
+//! and you are very sure that all values are initialized, you are able to
+//! disable the check provided by this module: pass the
+//! `pinned_init_unsafe_no_enforce_init` flag to rustc:
 //! ```text
 //! RUSTFLAGS="--cfg pinned_init_unsafe_no_enforce_init" cargo build
 //! ```
 //! This will also need to be done by all crates, that depend on your crate,
 //! because it is circumventing one of the safety guarantees of this crate and
-//! is expicitly opt-in. Please try to find a safe workaround or open an issue
-//! at [my repo].
+//! is expicitly opt-in. A value that was not fully initialized is then used
+//! in its initialized form, which is undefined behaviour. Please try to find a
+//! safe workaround or open an issue at [my
+//! repo](https://github.com/y86-dev/pinned-init/).
 
 use crate::{
-    private::{BeginInit, BeginPinnedInit},
-    Init, PinnedInit,
-};
-use core::{
-    mem::{self, MaybeUninit},
-    pin::Pin,
+    private::{BeginInit, BeginPinnedInit, PartialInit},
+    static_uninit::StaticUninit,
+    transmute::TransmuteInto,
+    Init, PinnedInit, TryInit, TryPinnedInit,
 };
+use core::{marker::PhantomData, mem::MaybeUninit, pin::Pin};
 
 /// A pointer to pinned data that needs to be initialized while pinned.
-/// When this pointer is neglected and not initialized, it will
-/// - panic on drop (when debug assertions are enabled)
-/// - produce a link time error (when debug assertions are disabled)
-/// This is to prevent partial initialization and guarantee statically (when
-/// used without debug assertions) that the type `T` is fully initialized and
-/// may be transmuted to its initialized form.
+/// When this pointer is neglected and not initialized, the initialization of
+/// the value containing it will panic. This is to prevent partial
+/// initialization and guarantee that the type `T` is fully initialized and may
+/// be transmuted to its initialized form.
 ///
 /// This pointer does **not** implement [`Deref`] or [`DerefMut`], instead you
 /// should only use [`NeedsPinnedInit::begin_init`] on this type to begin safe
@@ -73,107 +188,206 @@ use core::{
 /// # Invariants and assumptions
 ///
 /// - When the `'init` lifetime expires, the value this pointer pointed to, will
-/// be initialized and have changed its type to `T::Initialized`.
+///   be initialized and have changed its type to `T::Initialized`, or the
+///   initialization failed and the value will be dropped according to the flags
+///   of this pointer.
 /// - From the construction of a [`NeedsPinnedInit<'init, T>`] until the end of
-/// `'init` it assumes full control over the pointee. This means that no one
-/// else is allowed to access the underlying value.
+///   `'init` it assumes full control over the pointee. This means that no one
+///   else is allowed to access the underlying value.
+///
+/// The pointer is stored as a raw pointer and all pointers derived from it
+/// (e.g. via [`NeedsPinnedInit::begin_init`] or [`NeedsPinnedInit::self_ref`])
+/// share its provenance. Pointers to the value, that were taken before
+/// initializing its fields, thus stay valid.
 ///
 /// [`Deref`]: core::ops::Deref
 /// [`DerefMut`]: core::ops::DerefMut
-#[repr(transparent)]
-pub struct NeedsPinnedInit<'init, T: ?Sized> {
-    // need option here, otherwise `mem::forget`ing `self` in `begin_init` is
-    // not possible, because we would need to borrow `self` for `'init`, but
-    // `'init` ends only after we have been dropped.
-    inner: Option<Pin<&'init mut T>>,
+pub struct NeedsPinnedInit<'init, T: ?Sized + PartialInit> {
+    ptr: *mut T,
+    flags: &'init T::Flags,
+    _phantom: PhantomData<Pin<&'init mut T>>,
+}
+
+impl<'init, T: ?Sized + BeginPinnedInit> NeedsPinnedInit<'init, T> {
+    /// Begin to initialize the value behind this `NeedsPinnedInit`.
+    #[inline]
+    pub fn begin_init(self) -> <T as BeginPinnedInit>::OngoingInit<'init> {
+        unsafe {
+            // SAFETY: API internal contract is upheld, __begin_init has the
+            // same invariants as NeedsPinnedInit.
+            T::__begin_init(self.ptr, self.flags)
+        }
+    }
 }
 
-#[cfg(not(pinned_init_unsafe_no_enforce_init))]
-impl<'init, T: ?Sized> Drop for NeedsPinnedInit<'init, T> {
-    fn drop(&mut self) {
-        if_cfg! {
-            if (debug_assertions) {
-                panic!(
-                    "NeedsPinnedInit({:p}) was dropped, without prior initialization!",
-                    *self.inner.as_ref().unwrap()
-                );
-            } else {
-                extern "C" {
-                    #[link_name = "NeedsPinnedInit was dropped, without prior initialization."]
-                    fn trigger() -> !;
-                }
-                unsafe {
-                    // SAFETY: this function does not exist and will generate a
-                    // link error
-                    trigger();
-                }
-            }
+impl<'init, T> NeedsPinnedInit<'init, MaybeUninit<T>> {
+    /// Initialize the value behind this `NeedsPinnedInit`.
+    ///
+    /// Returns a pinned reference to the initialized value, that can be used
+    /// for the rest of `'init`.
+    #[inline]
+    pub fn init(self, value: T) -> Pin<&'init mut T> {
+        self.flags.set(true);
+        let ptr = self.ptr as *mut T;
+        unsafe {
+            // SAFETY: the pointer is valid for writes and the value is pinned.
+            ptr.write(value);
+            Pin::new_unchecked(&mut *ptr)
+        }
+    }
+}
+
+impl<'init, T> NeedsPinnedInit<'init, StaticUninit<T, false>> {
+    /// Initialize the value behind this `NeedsPinnedInit`.
+    ///
+    /// Returns a pinned reference to the initialized value, that can be used
+    /// for the rest of `'init`.
+    #[inline]
+    pub fn init(self, value: T) -> Pin<&'init mut T> {
+        self.flags.set(true);
+        // `StaticUninit<T, false>` is a `repr(transparent)` wrapper of
+        // `MaybeUninit<T>`.
+        let ptr = self.ptr as *mut T;
+        unsafe {
+            // SAFETY: the pointer is valid for writes and the value is pinned.
+            ptr.write(value);
+            Pin::new_unchecked(&mut *ptr)
         }
     }
 }
 
 impl<'init, T: PinnedInit> NeedsPinnedInit<'init, T> {
-    /// Begin to initialize the value behind this `NeedsPinnedInit`.
+    /// Initialize the value behind this `NeedsPinnedInit` using `param`.
+    ///
+    /// Returns a pinned reference to the initialized value, that can be used
+    /// for the rest of `'init` (e.g. to compute the parameter of another
+    /// field).
+    ///
+    /// # Panics
+    ///
+    /// Panics when [`PinnedInit::init_raw`] returns without fully initializing
+    /// the value.
     #[inline]
-    pub fn begin_init(mut self) -> <T as BeginPinnedInit>::OngoingInit<'init> {
-        let res = if let Some(inner) = self.inner.take() {
+    pub fn init_with(self, param: T::Param) -> Pin<&'init mut T::Initialized> {
+        T::init_raw(
             unsafe {
-                // SAFETY: API internal contract is upheld, __begin_init has the
-                // same invariants as NeedsPinnedInit.
-                inner.__begin_init()
-            }
-        } else {
-            unsafe {
-                // SAFETY: self.inner is never `take`n anywhere else. Because
-                // this function takes `self` by value, we know, that the option
-                // is populated, because we only create `NeedsPinnedInit` with
-                // inner set to `Some`.
-                core::hint::unreachable_unchecked();
-            }
-        };
-        mem::forget(self);
-        res
+                // SAFETY: the copy is only used before we hand out the
+                // initialized value, which is checked below.
+                NeedsPinnedInit::from_raw(self.ptr, self.flags)
+            },
+            param,
+        );
+        unsafe {
+            // SAFETY: the pointer is valid and pinned for `'init`.
+            into_initialized(self.ptr, self.flags)
+        }
     }
 }
 
-impl<'init, T> NeedsPinnedInit<'init, MaybeUninit<T>> {
-    /// Begin to initialize the value behind this `NeedsPinnedInit`.
+impl<'init, T: TryPinnedInit> NeedsPinnedInit<'init, T> {
+    /// Try to initialize the value behind this `NeedsPinnedInit` using
+    /// `param`.
+    ///
+    /// Returns a pinned reference to the initialized value, that can be used
+    /// for the rest of `'init`. When the initialization fails, the error is
+    /// returned and the value is dropped, when the initialization of the
+    /// containing value fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics when [`TryPinnedInit::try_init_raw`] returns `Ok(())` without
+    /// fully initializing the value.
     #[inline]
-    pub fn init(mut self, value: T) {
-        let res = if let Some(inner) = self.inner.take() {
-            unsafe {
-                // SAFETY: we never move out of the reference
-                inner.get_unchecked_mut().write(value);
-            }
-        } else {
+    pub fn try_init_with(
+        self,
+        param: T::Param,
+    ) -> Result<Pin<&'init mut T::Initialized>, T::Error> {
+        T::try_init_raw(
             unsafe {
-                // SAFETY: self.inner is never `take`n anywhere else. Because
-                // this function takes `self` by value, we know, that the option
-                // is populated, because we only create `NeedsPinnedInit` with
-                // inner set to `Some`.
-                core::hint::unreachable_unchecked();
-            }
-        };
-        mem::forget(self);
-        res
+                // SAFETY: the copy is only used before we hand out the
+                // initialized value, which is checked below.
+                NeedsPinnedInit::from_raw(self.ptr, self.flags)
+            },
+            param,
+        )?;
+        Ok(unsafe {
+            // SAFETY: the pointer is valid and pinned for `'init`.
+            into_initialized(self.ptr, self.flags)
+        })
     }
 }
 
-impl<'init, T: ?Sized> NeedsPinnedInit<'init, T> {
+/// Checks that the value behind `ptr` has been fully initialized and
+/// converts it to its initialized form.
+///
+/// # Panics
+///
+/// Panics when the value is not fully initialized, the containing value will
+/// drop it according to `flags`.
+///
+/// # Safety
+///
+/// `ptr` needs to be valid and pinned for `'init`.
+#[cfg_attr(pinned_init_unsafe_no_enforce_init, allow(unused_variables))]
+unsafe fn into_initialized<'init, T, U>(ptr: *mut T, flags: &T::Flags) -> Pin<&'init mut U>
+where
+    T: PartialInit + TransmuteInto<U>,
+{
+    #[cfg(not(pinned_init_unsafe_no_enforce_init))]
+    if !unsafe {
+        // SAFETY: `ptr` is valid and `flags` track its initialization.
+        T::__is_init(ptr, flags)
+    } {
+        panic!(
+            "The value at {:p} was not fully initialized, but its initializer returned successfully!",
+            ptr
+        );
+    }
+    unsafe {
+        // SAFETY: the value has been fully initialized and stays pinned.
+        Pin::new_unchecked(&mut *(T::transmute_ptr(ptr) as *mut U))
+    }
+}
+
+impl<'init, T: ?Sized + PartialInit> NeedsPinnedInit<'init, T> {
     /// Construct a new `NeedsPinnedInit` from the given [`Pin`].
     ///
     /// # Safety
     ///
     /// - When the `'init` lifetime expires, the value at `inner` will be
-    /// initialized and have changed type to `T::Initialized`.
+    ///   initialized and have changed type to `T::Initialized`, or the
+    ///   initialization failed and the value will be dropped according to `flags`.
     /// - From the moment this function is called until the end of `'init` the
-    /// produced [`NeedsPinnedInit`] becomes the only valid way to access the
-    /// underlying value.
+    ///   produced [`NeedsPinnedInit`] becomes the only valid way to access the
+    ///   underlying value.
     /// - The caller needs to guarantee, that the pointer from which `inner` was
-    /// derived changes its pointee type to `T::Initialized`, when `'init` ends.
+    ///   derived changes its pointee type to `T::Initialized`, when `'init` ends.
+    /// - `flags` need to track the initialization of the value at `inner`.
     #[inline]
-    pub unsafe fn new_unchecked(inner: Pin<&'init mut T>) -> Self {
-        Self { inner: Some(inner) }
+    pub unsafe fn new_unchecked(inner: Pin<&'init mut T>, flags: &'init T::Flags) -> Self {
+        unsafe {
+            // SAFETY: the caller upholds the requirements, the pointer is
+            // derived from a valid pinned reference.
+            Self::from_raw(inner.get_unchecked_mut(), flags)
+        }
+    }
+
+    /// Construct a new `NeedsPinnedInit` from the given raw pointer.
+    ///
+    /// Unlike [`NeedsPinnedInit::new_unchecked`], this does not create a
+    /// reference to the value, so raw pointers to the value stay valid.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` needs to be valid for reads and writes and pinned for `'init`.
+    /// - the requirements of [`NeedsPinnedInit::new_unchecked`] need to hold.
+    #[inline]
+    pub unsafe fn from_raw(ptr: *mut T, flags: &'init T::Flags) -> Self {
+        Self {
+            ptr,
+            flags,
+            _phantom: PhantomData,
+        }
     }
 
     /// Map the inner value to another contained within the first, this is only safe, if the outer
@@ -185,18 +399,34 @@ impl<'init, T: ?Sized> NeedsPinnedInit<'init, T> {
     /// argument does not move. Also the caller is not allowed to move out of the argument given to
     /// `map`.
     /// The wrapping value is also required to be fully initialized.
-    pub unsafe fn map_unchecked<U: ?Sized>(
-        mut self,
+    pub unsafe fn map_unchecked<U: ?Sized + PartialInit<Flags = T::Flags>>(
+        self,
         map: impl FnOnce(&mut T) -> &mut U,
     ) -> NeedsPinnedInit<'init, U> {
-        let inner = unsafe {
+        unsafe {
             // SAFETY: the caller guarantees that this is safe
-            self.inner.take().unwrap().map_unchecked_mut(map)
-        };
-        mem::forget(self);
+            NeedsPinnedInit::from_raw(map(&mut *self.ptr), self.flags)
+        }
+    }
+
+    /// Map the pointer to the inner value to a pointer to another value contained within the
+    /// first, this is only safe, if the outer wrapper type does not require initialization.
+    ///
+    /// Unlike [`NeedsPinnedInit::map_unchecked`] this does not create a reference to the outer
+    /// value, so raw pointers to it stay valid.
+    ///
+    /// # Safety
+    ///
+    /// The caller needs to guarantee, that the pointer returned by `map` is derived from its
+    /// argument and points to a value that will not move so long as the argument does not move.
+    /// The wrapping value is also required to be fully initialized.
+    pub unsafe fn map_ptr_unchecked<U: ?Sized + PartialInit<Flags = T::Flags>>(
+        self,
+        map: impl FnOnce(*mut T) -> *mut U,
+    ) -> NeedsPinnedInit<'init, U> {
         unsafe {
             // SAFETY: the caller guarantees that this is safe
-            NeedsPinnedInit::new_unchecked(inner)
+            NeedsPinnedInit::from_raw(map(self.ptr), self.flags)
         }
     }
 
@@ -221,15 +451,7 @@ impl<'init, T: ?Sized> NeedsPinnedInit<'init, T> {
     /// [`UnsafeCell`]: core::cell::UnsafeCell
     #[inline]
     pub fn as_ptr(&self) -> *const T {
-        let ptr: Pin<&T> = unsafe {
-            // SAFETY: self.inner can only be None, if `begin_init` was called,
-            // which takes `self` by value, so this function cannot be called.
-            // `NeedsPinnedInit` is only initialized with Some, so inner is
-            // populated.
-            self.inner.as_ref().unwrap_unchecked()
-        }
-        .as_ref();
-        (&*ptr) as *const T
+        self.ptr
     }
 
     /// Get a raw mutable pointer to the value behind this `NeedsPinnedInit`.
@@ -253,34 +475,305 @@ impl<'init, T: ?Sized> NeedsPinnedInit<'init, T> {
     /// [`UnsafeCell`]: core::cell::UnsafeCell
     #[inline]
     pub fn as_ptr_mut(&mut self) -> *mut T {
-        let ptr: Pin<&mut T> = unsafe {
-            // SAFETY: self.inner can only be None, if `begin_init` was called,
-            // which takes `self` by value, so this function cannot be called.
-            // `NeedsPinnedInit` is only initialized with Some, so inner is
-            // populated.
-            self.inner.as_mut().unwrap_unchecked()
-        }
-        .as_mut();
-        unsafe {
-            // SAFETY: the caller is responsible to handle this pointer as a
-            // pinned pointer.
-            ptr.get_unchecked_mut() as *mut T
-        }
+        self.ptr
+    }
+
+    /// Get a [`SelfRef<U>`] to the value behind this `NeedsPinnedInit`, typed as
+    /// its initialized form `U`.
+    ///
+    /// The value is pinned, so the pointer stays valid until the value is
+    /// dropped. See [`SelfRef`] for the rules when dereferencing it.
+    #[inline]
+    pub fn self_ref<U>(&mut self) -> SelfRef<U>
+    where
+        T: TransmuteInto<U>,
+    {
+        // `T: TransmuteInto<U>` guarantees, that `T` and `U` have the same
+        // layout. We cannot use `transmute_ptr`, the value is not yet
+        // initialized.
+        SelfRef::from_raw(self.as_ptr_mut() as *mut U)
     }
 
     /// # Safety
     /// The caller needs to ensure that the value is initialized.
     pub unsafe fn assume_init(self) {
-        mem::forget(self);
+        T::__set_init(self.flags);
     }
 }
 
+/// A raw pointer to a pinned value, that can be stored inside of the value
+/// itself (or one of its neighbours) to create self-referential types.
+///
+/// Use [`NeedsPinnedInit::self_ref`] to get a `SelfRef` to a value that is being
+/// initialized and [`SelfRef::new`] for a pinned field that does not need
+/// initialization. Creating, copying and casting a `SelfRef` is safe, only
+/// dereferencing the pointer needs `unsafe`:
+/// - the pointer is valid until the pointee is dropped, because it is pinned.
+/// - while the pointee is being initialized, it may only be dereferenced by the
+///   code initializing it. Afterwards it may only be dereferenced as the
+///   initialized type.
+/// - a `*mut T` obtained via [`SelfRef::as_mut_ptr`] may only be used to create
+///   a `&mut T` or to write to the pointee, if the pointee is wrapped inside of an
+///   [`UnsafeCell`] or an `UnsafeAliasCell`.
+///
+/// ```rust
+/// # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+/// use core::{marker::PhantomPinned, mem::MaybeUninit, pin::Pin};
+/// use pinned_init::{needs_init::SelfRef, prelude::*};
+///
+/// #[manual_init(pinned)]
+/// pub struct Cursor {
+///     buf: [u8; 4],
+///     #[init]
+///     #[uninit = MaybeUninit::<*const u8>]
+///     end: *const u8,
+///     #[init]
+///     #[uninit = MaybeUninit::<*const Cursor>]
+///     this: *const Cursor,
+///     #[pin]
+///     _pin: PhantomPinned,
+/// }
+///
+/// impl PinnedInit for CursorUninit {
+///     type Initialized = Cursor;
+///     type Param = ();
+///
+///     fn init_raw(mut this: NeedsPinnedInit<Self>, _: ()) {
+///         let me: SelfRef<Cursor> = this.self_ref();
+///         let CursorOngoingInit { buf, end, this, _pin } = this.begin_init();
+///         end.init(buf.as_ptr_range().end);
+///         this.init(me.as_ptr());
+///     }
+/// }
+///
+/// let uninit = CursorUninit {
+///     buf: [1, 2, 3, 4],
+///     end: MaybeUninit::uninit(),
+///     this: MaybeUninit::uninit(),
+///     _pin: PhantomPinned,
+/// };
+/// let cursor = Box::pin(uninit).init();
+/// assert_eq!(cursor.this, &*cursor as *const Cursor);
+/// assert_eq!(cursor.end, cursor.buf.as_ptr_range().end);
+/// ```
+///
+/// [`UnsafeCell`]: core::cell::UnsafeCell
+pub struct SelfRef<T: ?Sized> {
+    ptr: *mut T,
+}
+
+impl<T: ?Sized> SelfRef<T> {
+    /// Get a `SelfRef` to a pinned value.
+    #[inline]
+    pub fn new(value: Pin<&mut T>) -> Self {
+        Self::from_raw(unsafe {
+            // SAFETY: we only create a raw pointer, the caller needs to handle
+            // it as a pinned pointer.
+            value.get_unchecked_mut() as *mut T
+        })
+    }
+
+    #[inline]
+    fn from_raw(ptr: *mut T) -> Self {
+        Self { ptr }
+    }
+
+    /// Returns the raw pointer.
+    #[inline]
+    pub fn as_ptr(self) -> *const T {
+        self.ptr
+    }
+
+    /// Returns the raw mutable pointer.
+    #[inline]
+    pub fn as_mut_ptr(self) -> *mut T {
+        self.ptr
+    }
+}
+
+impl<T> SelfRef<T> {
+    /// Casts the pointer to a pointer of another type.
+    #[inline]
+    pub fn cast<U>(self) -> SelfRef<U> {
+        SelfRef::from_raw(self.ptr as *mut U)
+    }
+}
+
+impl<T: ?Sized> Clone for SelfRef<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for SelfRef<T> {}
+
+/// A pointer to a pinned slice, whose elements need to be initialized while
+/// pinned. Created by [`AllocUninitSlice::init_slice`].
+///
+/// Use [`NeedsPinnedInitSlice::split_at`] or iterate over this pointer to get a
+/// [`NeedsPinnedInit`] for every element. The same invariants as for
+/// [`NeedsPinnedInit`] apply to every element of the slice.
+///
+/// [`AllocUninitSlice::init_slice`]: crate::ptr::AllocUninitSlice::init_slice
+pub struct NeedsPinnedInitSlice<'init, T: PartialInit> {
+    /// Pointer to the first element, the slice has the same length as `flags`.
+    ptr: *mut T,
+    flags: &'init [T::Flags],
+    _phantom: PhantomData<Pin<&'init mut [T]>>,
+}
+
+impl<'init, T: PartialInit> NeedsPinnedInitSlice<'init, T> {
+    /// Construct a new `NeedsPinnedInitSlice` from the given [`Pin`].
+    ///
+    /// # Safety
+    ///
+    /// - `inner` and `flags` need to have the same length.
+    /// - the safety requirements of [`NeedsPinnedInit::new_unchecked`] need to
+    ///   hold for every element of `inner` and its flags.
+    #[inline]
+    pub unsafe fn new_unchecked(inner: Pin<&'init mut [T]>, flags: &'init [T::Flags]) -> Self {
+        let inner = unsafe {
+            // SAFETY: we never move the elements, they stay pinned.
+            inner.get_unchecked_mut()
+        };
+        debug_assert_eq!(inner.len(), flags.len());
+        unsafe {
+            // SAFETY: the caller upholds the requirements, the pointer is
+            // derived from a valid pinned reference.
+            Self::from_raw(inner.as_mut_ptr(), flags)
+        }
+    }
+
+    /// Construct a new `NeedsPinnedInitSlice` from a raw pointer to its first
+    /// element, the length of the slice is the length of `flags`.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` needs to be valid for reads and writes of `flags.len()` elements
+    ///   and pinned for `'init`.
+    /// - the safety requirements of [`NeedsPinnedInit::from_raw`] need to hold
+    ///   for every element and its flags.
+    #[inline]
+    pub unsafe fn from_raw(ptr: *mut T, flags: &'init [T::Flags]) -> Self {
+        Self {
+            ptr,
+            flags,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements in the slice.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.flags.len()
+    }
+
+    /// Returns `true` if the slice has no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.flags.is_empty()
+    }
+
+    /// Divides the slice into two at `mid`, the first one contains the elements
+    /// `[0, mid)` and the second one the elements `[mid, len)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
+    #[inline]
+    pub fn split_at(self, mid: usize) -> (Self, Self) {
+        // panics if `mid > len`.
+        let (left_flags, right_flags) = self.flags.split_at(mid);
+        unsafe {
+            // SAFETY: both halves inherit the invariants of `self`, `mid <= len`
+            // so the second half starts inside of (or one past) the slice.
+            (
+                Self::from_raw(self.ptr, left_flags),
+                Self::from_raw(self.ptr.add(mid), right_flags),
+            )
+        }
+    }
+
+    /// Returns the first element and the rest of the slice, or `None` if the
+    /// slice is empty.
+    #[inline]
+    pub fn split_first(self) -> Option<(NeedsPinnedInit<'init, T>, Self)> {
+        if self.is_empty() {
+            return None;
+        }
+        let (first, rest) = self.split_at(1);
+        Some((first.into_single(), rest))
+    }
+
+    /// Returns the last element and the rest of the slice, or `None` if the
+    /// slice is empty.
+    #[inline]
+    pub fn split_last(self) -> Option<(NeedsPinnedInit<'init, T>, Self)> {
+        if self.is_empty() {
+            return None;
+        }
+        let mid = self.len() - 1;
+        let (rest, last) = self.split_at(mid);
+        Some((last.into_single(), rest))
+    }
+
+    /// Converts a slice of length one into a pointer to its only element.
+    fn into_single(self) -> NeedsPinnedInit<'init, T> {
+        unsafe {
+            // SAFETY: the element inherits the invariants of `self`.
+            NeedsPinnedInit::from_raw(self.ptr, &self.flags[0])
+        }
+    }
+}
+
+impl<'init, T: PartialInit> IntoIterator for NeedsPinnedInitSlice<'init, T> {
+    type Item = NeedsPinnedInit<'init, T>;
+    type IntoIter = SliceIter<'init, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        SliceIter { rest: Some(self) }
+    }
+}
+
+/// An iterator over the elements of a [`NeedsPinnedInitSlice`].
+pub struct SliceIter<'init, T: PartialInit> {
+    rest: Option<NeedsPinnedInitSlice<'init, T>>,
+}
+
+impl<'init, T: PartialInit> Iterator for SliceIter<'init, T> {
+    type Item = NeedsPinnedInit<'init, T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (first, rest) = self.rest.take()?.split_first()?;
+        self.rest = Some(rest);
+        Some(first)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.rest.as_ref().map_or(0, NeedsPinnedInitSlice::len);
+        (len, Some(len))
+    }
+}
+
+impl<'init, T: PartialInit> DoubleEndedIterator for SliceIter<'init, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (last, rest) = self.rest.take()?.split_last()?;
+        self.rest = Some(rest);
+        Some(last)
+    }
+}
+
+impl<'init, T: PartialInit> ExactSizeIterator for SliceIter<'init, T> {}
+
 /// A pointer to data that needs to be initialized.
-/// When this pointer is neglected and not initialized, it will
-/// - panic on drop (when debug assertions are enabled)
-/// - produce a link time error (when debug assertions are disabled)
-/// This is to prevent partial initialization and guarantee statically (when
-/// used without debug assertions) that the type `T` is fully initialized.
+/// When this pointer is neglected and not initialized, the initialization of
+/// the value containing it will panic. This is to prevent partial
+/// initialization and guarantee that the type `T` is fully initialized.
 ///
 /// This pointer does **not** implement [`Deref`] or [`DerefMut`], instead you
 /// should only use [`NeedsInit::init`] on this type to safely initialize
@@ -289,101 +782,138 @@ impl<'init, T: ?Sized> NeedsPinnedInit<'init, T> {
 /// # Invariants and assumptions
 ///
 /// - When the `'init` lifetime expires, the value this pointer pointed to, will
-/// be initialized.
+///   be initialized, or the initialization failed and the value will be dropped
+///   according to the flags of this pointer.
 /// - From the construction of a [`NeedsInit<'init, T>`] until the end of
-/// `'init` it assumes full control over the pointee. This means that no one
-/// else is allowed to access the underlying value.
+///   `'init` it assumes full control over the pointee. This means that no one
+///   else is allowed to access the underlying value.
 ///
 /// [`Deref`]: core::ops::Deref
 /// [`DerefMut`]: core::ops::DerefMut
-#[repr(transparent)]
-pub struct NeedsInit<'init, T> {
-    inner: Option<&'init mut T>,
-}
-
-#[cfg(not(pinned_init_unsafe_no_enforce_init))]
-impl<'init, T> Drop for NeedsInit<'init, T> {
-    #[inline]
-    fn drop(&mut self) {
-        if_cfg! {
-            if (debug_assertions) {
-                panic!(
-                    "NeedsInit({:p}) was dropped, without prior initialization!",
-                    self.inner.as_ref().unwrap()
-                );
-            } else {
-                extern "C" {
-                    #[link_name = "NeedsInit was dropped, without prior initialization."]
-                    fn trigger() -> !;
-                }
-                unsafe {
-                    // SAFETY: this function does not exist and will generate a
-                    // link error
-                    trigger();
-                }
-            }
-        }
-    }
+pub struct NeedsInit<'init, T: PartialInit> {
+    inner: &'init mut T,
+    flags: &'init T::Flags,
 }
 
-impl<'init, T> NeedsInit<'init, T> {
+impl<'init, T: PartialInit> NeedsInit<'init, T> {
     /// Construct a new `NeedsInit` from the given pointer.
     ///
     /// # Safety
     ///
     /// - When the `'init` lifetime expires, the value at `inner` will be
-    /// initialized and have changed type to `StaticUninit<T, true>`.
+    ///   initialized and have changed type to `T::Initialized`, or the
+    ///   initialization failed and the value will be dropped according to `flags`.
     /// - From the moment this function is called until the end of `'init` the
-    /// produced [`NeedsInit`] becomes the only valid way to access the
-    /// underlying value.
+    ///   produced [`NeedsInit`] becomes the only valid way to access the
+    ///   underlying value.
     /// - The caller needs to guarantee, that the pointer from which `inner` was
-    /// derived changes its pointee type to `StaticUninit<T, true>`, when `'init` ends.
+    ///   derived changes its pointee type to `T::Initialized`, when `'init` ends.
+    /// - `flags` need to track the initialization of the value at `inner`.
     #[inline]
-    pub unsafe fn new_unchecked(inner: &'init mut T) -> Self {
-        Self { inner: Some(inner) }
+    pub unsafe fn new_unchecked(inner: &'init mut T, flags: &'init T::Flags) -> Self {
+        Self { inner, flags }
     }
 }
 
 impl<'init, T> NeedsInit<'init, MaybeUninit<T>> {
-    /// Begin to initialize the value behind this `NeedsPinnedInit`.
+    /// Initialize the value behind this `NeedsInit`.
+    ///
+    /// Returns a reference to the initialized value, that can be used for the
+    /// rest of `'init`.
     #[inline]
-    pub fn init(mut self, value: T) {
-        let res = if let Some(inner) = self.inner.take() {
-            inner.write(value);
-        } else {
-            unsafe {
-                // SAFETY: self.inner is never `take`n anywhere else. Because
-                // this function takes `self` by value, we know, that the option
-                // is populated, because we only create `NeedsPinnedInit` with
-                // inner set to `Some`.
-                core::hint::unreachable_unchecked();
-            }
-        };
-        mem::forget(self);
-        res
+    pub fn init(self, value: T) -> &'init mut T {
+        self.flags.set(true);
+        self.inner.write(value)
+    }
+}
+
+impl<'init, T> NeedsInit<'init, StaticUninit<T, false>> {
+    /// Initialize the value behind this `NeedsInit`.
+    ///
+    /// Returns a reference to the initialized value, that can be used for the
+    /// rest of `'init`.
+    #[inline]
+    pub fn init(self, value: T) -> &'init mut T {
+        self.flags.set(true);
+        let ptr = self.inner.as_mut_ptr();
+        unsafe {
+            // SAFETY: the pointer is valid for writes.
+            ptr.write(value);
+            &mut *ptr
+        }
     }
 }
 
 impl<'init, T: Init> NeedsInit<'init, T> {
-    /// Begin to initialize the value behind this `NeedsPinnedInit`.
+    /// Initialize the value behind this `NeedsInit` using `param`.
+    ///
+    /// Returns a reference to the initialized value, that can be used for the
+    /// rest of `'init`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when [`Init::init_raw`] returns without fully initializing the
+    /// value.
     #[inline]
-    pub fn begin_init(mut self) -> <T as BeginInit>::OngoingInit<'init> {
-        let res = if let Some(inner) = self.inner.take() {
+    pub fn init_with(self, param: T::Param) -> &'init mut T::Initialized {
+        let Self { inner, flags } = self;
+        T::init_raw(
             unsafe {
-                // SAFETY: API internal contract is upheld, __begin_init has the
-                // same invariants as NeedsInit.
-                inner.__begin_init()
-            }
-        } else {
+                // SAFETY: the reborrow ends before we hand out the initialized
+                // value, which is checked below.
+                NeedsInit::new_unchecked(&mut *inner, flags)
+            },
+            param,
+        );
+        into_initialized_unpinned(inner, flags)
+    }
+}
+
+impl<'init, T: TryInit> NeedsInit<'init, T> {
+    /// Try to initialize the value behind this `NeedsInit` using `param`.
+    ///
+    /// Returns a reference to the initialized value, that can be used for the
+    /// rest of `'init`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when [`TryInit::try_init_raw`] returns `Ok(())` without fully
+    /// initializing the value.
+    #[inline]
+    pub fn try_init_with(self, param: T::Param) -> Result<&'init mut T::Initialized, T::Error> {
+        let Self { inner, flags } = self;
+        T::try_init_raw(
             unsafe {
-                // SAFETY: self.inner is never `take`n anywhere else. Because
-                // this function takes `self` by value, we know, that the option
-                // is populated, because we only create `NeedsPinnedInit` with
-                // inner set to `Some`.
-                core::hint::unreachable_unchecked();
-            }
-        };
-        mem::forget(self);
-        res
+                // SAFETY: the reborrow ends before we hand out the initialized
+                // value, which is checked below.
+                NeedsInit::new_unchecked(&mut *inner, flags)
+            },
+            param,
+        )?;
+        Ok(into_initialized_unpinned(inner, flags))
+    }
+}
+
+/// Unpinned version of [`into_initialized`].
+fn into_initialized_unpinned<'init, T, U>(inner: &'init mut T, flags: &T::Flags) -> &'init mut U
+where
+    T: PartialInit + TransmuteInto<U>,
+{
+    unsafe {
+        // SAFETY: the value is not pinned, so treating it as pinned for the
+        // check and unpinning the result is fine.
+        Pin::into_inner_unchecked(into_initialized(inner, flags))
+    }
+}
+
+impl<'init, T: BeginInit> NeedsInit<'init, T> {
+    /// Begin to initialize the value behind this `NeedsInit`.
+    #[inline]
+    pub fn begin_init(self) -> <T as BeginInit>::OngoingInit<'init> {
+        unsafe {
+            // SAFETY: API internal contract is upheld, __begin_init has the
+            // same invariants as NeedsInit.
+            self.inner.__begin_init(self.flags)
+        }
     }
 }
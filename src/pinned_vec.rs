@@ -0,0 +1,262 @@
+//! Module providing [`PinnedVec<T>`], a growable collection of pinned values
+//! that are initialized in place and never move.
+//!
+//! The elements are stored in fixed size chunks, growing the collection only
+//! allocates a new chunk and never moves the existing elements. Every element
+//! keeps its index until it is removed, removed elements are dropped in place
+//! and their slot is reused by later pushes:
+//! ```rust
+//! # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+//! use core::{mem::MaybeUninit, pin::Pin};
+//! use pinned_init::{pinned_vec::PinnedVec, prelude::*};
+//!
+//! #[manual_init(pinned)]
+//! pub struct Conn {
+//!     #[init]
+//!     id: usize,
+//! }
+//!
+//! impl PinnedInit for ConnUninit {
+//!     type Initialized = Conn;
+//!     type Param = usize;
+//!
+//!     fn init_raw(this: NeedsPinnedInit<Self>, id: usize) {
+//!         let ConnOngoingInit { id: slot } = this.begin_init();
+//!         slot.init(id);
+//!     }
+//! }
+//!
+//! let mut conns = PinnedVec::new();
+//! for id in 0..100 {
+//!     let conn: Pin<&mut Conn> = conns.push_init(ConnUninit { id: MaybeUninit::uninit() }, id);
+//!     assert_eq!(conn.id, id);
+//! }
+//! assert!(conns.remove(42));
+//! assert!(conns.get(42).is_none());
+//! assert_eq!(conns[43].id, 43);
+//! // the slot of the removed element is reused.
+//! assert_eq!(conns.next_index(), 42);
+//! conns.push_init(ConnUninit { id: MaybeUninit::uninit() }, 1000);
+//! assert_eq!(conns[42].id, 1000);
+//! assert_eq!(conns.len(), 100);
+//! ```
+
+use crate::{
+    needs_init::NeedsPinnedInit, private::BeginPinnedInit, transmute::TransmuteInto, PinnedInit,
+    TryPinnedInit,
+};
+use alloc::{boxed::Box, vec::Vec};
+use core::{convert::Infallible, mem::MaybeUninit, ops::Index, pin::Pin, ptr};
+
+/// The number of elements stored in one chunk.
+const CHUNK_LEN: usize = 32;
+
+/// A growable collection of pinned values, that are initialized in place.
+///
+/// See the [module-level documentation](self) for an example.
+pub struct PinnedVec<T> {
+    chunks: Vec<Box<[MaybeUninit<T>]>>,
+    /// Tracks which slots hold an element, one entry for every slot that has
+    /// ever been used.
+    occupied: Vec<bool>,
+    /// Indices of slots that have been used before, but are now empty.
+    free: Vec<usize>,
+    len: usize,
+}
+
+impl<T> PinnedVec<T> {
+    /// Creates a new empty collection without allocating.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            chunks: Vec::new(),
+            occupied: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the index the next pushed element will have.
+    #[inline]
+    pub fn next_index(&self) -> usize {
+        match self.free.last() {
+            Some(&index) => index,
+            None => self.occupied.len(),
+        }
+    }
+
+    /// Moves `uninit` into a free slot and initializes it in place using
+    /// `param`. The element is located at [`Self::next_index`].
+    ///
+    /// When the initializer panics, the partially initialized value is dropped
+    /// and the slot stays free.
+    #[inline]
+    pub fn push_init<U>(&mut self, uninit: U, param: U::Param) -> Pin<&mut T>
+    where
+        U: PinnedInit<Initialized = T>,
+    {
+        match self.push_inner(uninit, |this| {
+            U::init_raw(this, param);
+            Ok::<(), Infallible>(())
+        }) {
+            Ok(this) => this,
+            Err(e) => match e {},
+        }
+    }
+
+    /// Moves `uninit` into a free slot and tries to initialize it in place
+    /// using `param`. The element is located at [`Self::next_index`].
+    ///
+    /// When the initialization fails, the partially initialized value is
+    /// dropped and the slot stays free.
+    #[inline]
+    pub fn try_push_init<U>(&mut self, uninit: U, param: U::Param) -> Result<Pin<&mut T>, U::Error>
+    where
+        U: TryPinnedInit<Initialized = T>,
+    {
+        self.push_inner(uninit, |this| U::try_init_raw(this, param))
+    }
+
+    fn push_inner<U, E>(
+        &mut self,
+        uninit: U,
+        init: impl FnOnce(NeedsPinnedInit<'_, U>) -> Result<(), E>,
+    ) -> Result<Pin<&mut T>, E>
+    where
+        U: BeginPinnedInit + TransmuteInto<T>,
+    {
+        let index = self.next_index();
+        if index / CHUNK_LEN == self.chunks.len() {
+            self.chunks.push(Box::new_uninit_slice(CHUNK_LEN));
+        }
+        let ptr = self.slot_ptr(index) as *mut U;
+        unsafe {
+            // SAFETY: `U` and `T` have the same layout, because `U: TransmuteInto<T>`
+            // and the slot is free.
+            ptr.write(uninit);
+            // SAFETY: we have unique access to the slot, which is never moved.
+            // When the initialization fails, the value was dropped and the slot
+            // stays free.
+            crate::init_in_place(Pin::new_unchecked(&mut *ptr), init)?;
+        }
+        if index == self.occupied.len() {
+            self.occupied.push(true);
+        } else {
+            self.free.pop();
+            self.occupied[index] = true;
+        }
+        self.len += 1;
+        Ok(unsafe {
+            // SAFETY: the value has been fully initialized and is never moved.
+            Pin::new_unchecked(&mut *(U::transmute_ptr(ptr) as *mut T))
+        })
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if there is no
+    /// element at `index`.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if !self.is_occupied(index) {
+            return None;
+        }
+        Some(unsafe {
+            // SAFETY: the slot holds an initialized element.
+            self.chunks[index / CHUNK_LEN][index % CHUNK_LEN].assume_init_ref()
+        })
+    }
+
+    /// Returns a pinned mutable reference to the element at `index`, or `None`
+    /// if there is no element at `index`.
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<Pin<&mut T>> {
+        if !self.is_occupied(index) {
+            return None;
+        }
+        Some(unsafe {
+            // SAFETY: the slot holds an initialized element, which is never
+            // moved.
+            Pin::new_unchecked(&mut *self.slot_ptr(index))
+        })
+    }
+
+    /// Drops the element at `index` in place, returns `false` if there is no
+    /// element at `index`.
+    ///
+    /// The slot of the element is reused by later pushes.
+    pub fn remove(&mut self, index: usize) -> bool {
+        if !self.is_occupied(index) {
+            return false;
+        }
+        self.occupied[index] = false;
+        self.free.push(index);
+        self.len -= 1;
+        unsafe {
+            // SAFETY: the slot held an initialized element, which is not used
+            // again.
+            ptr::drop_in_place(self.slot_ptr(index));
+        }
+        true
+    }
+
+    /// Returns an iterator over all elements and their indices.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> + '_ {
+        (0..self.occupied.len()).filter_map(|index| Some((index, self.get(index)?)))
+    }
+
+    #[inline]
+    fn is_occupied(&self, index: usize) -> bool {
+        self.occupied.get(index).copied().unwrap_or(false)
+    }
+
+    #[inline]
+    fn slot_ptr(&mut self, index: usize) -> *mut T {
+        self.chunks[index / CHUNK_LEN][index % CHUNK_LEN].as_mut_ptr()
+    }
+}
+
+impl<T> Default for PinnedVec<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<usize> for PinnedVec<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        match self.get(index) {
+            Some(elem) => elem,
+            None => panic!("there is no element at index {}", index),
+        }
+    }
+}
+
+impl<T> Drop for PinnedVec<T> {
+    fn drop(&mut self) {
+        for index in 0..self.occupied.len() {
+            if self.occupied[index] {
+                self.occupied[index] = false;
+                unsafe {
+                    // SAFETY: the slot holds an initialized element, which is
+                    // not used again.
+                    ptr::drop_in_place(self.slot_ptr(index));
+                }
+            }
+        }
+    }
+}
@@ -8,8 +8,14 @@
 //! The type system is used to enforce as much as possible, but implementors
 //! still need to pay attention, that their type can implemen [`OwnedUniquePtr<T>`].
 
-use crate::transmute::TransmuteInto;
-use core::{ops::DerefMut, pin::Pin};
+#[cfg(feature = "alloc")]
+use crate::needs_init::NeedsPinnedInitSlice;
+use crate::{
+    private::WriteUninit, transmute::TransmuteInto, DefaultParam, PinnedInit, TryPinnedInit,
+};
+#[cfg(feature = "alloc")]
+use core::ops::Deref;
+use core::{convert::Infallible, mem::MaybeUninit, ops::DerefMut, pin::Pin};
 
 // used to dissallow other crates implementing TypesEq.
 mod sealed {
@@ -53,6 +59,9 @@ where
     /// This function does not
     /// - move the pointee.
     /// - mutate the pointee.
+    /// - create a reference to the pointee, so raw pointers to it (e.g. stored
+    ///   inside of the pointee itself) stay valid.
+    ///
     /// The caller needs to guarantee, that it is safe to transmute `T` to `U` (or
     /// equivalently, that it is safe to call [`TransmuteInto::transmute_ptr`]).
     unsafe fn transmute_pointee_pinned<U>(this: Pin<Self>) -> Pin<Self::Ptr<U>>
@@ -60,7 +69,223 @@ where
         T: TransmuteInto<U>;
 }
 
+/// An [`OwnedUniquePtr<T>`] that can allocate memory for a `T` without
+/// initializing it.
+///
+/// This allows writing the fields of the uninitialized variant of a struct
+/// directly into their final allocation, see the [`slot`](crate::slot) module.
+///
+/// # Safety
+///
+/// - [`Self::alloc_uninit`] needs to return a new allocation that is valid for
+///   a `T`.
+/// - [`Self::assume_init_pinned`] must only change the type of the pointer, it
+///   must not move the pointee.
+pub unsafe trait AllocUninit<T>: OwnedUniquePtr<T> {
+    /// Allocates memory for a `T` without initializing it.
+    fn alloc_uninit() -> Pin<Self::Ptr<MaybeUninit<T>>>;
+
+    /// Converts the pointer to the now fully written value.
+    ///
+    /// # Safety
+    ///
+    /// The value behind `this` needs to be fully written.
+    unsafe fn assume_init_pinned(this: Pin<Self::Ptr<MaybeUninit<T>>>) -> Pin<Self>;
+
+    /// Allocates a new `T`, writes its fields using `write` and then
+    /// initializes it.
+    #[inline]
+    fn write_init(write: impl FnOnce(T::Slots<'_>)) -> Pin<Self::Ptr<T::Initialized>>
+    where
+        T: PinnedInit + WriteUninit,
+        T::Param: DefaultParam,
+    {
+        Self::write_init_with(write, DefaultParam::default_param())
+    }
+
+    /// Allocates a new `T`, writes its fields using `write` and then
+    /// initializes it using `param`.
+    ///
+    /// When `write` or the initializer panics, the written and initialized
+    /// fields are dropped and the memory is freed before the panic continues to
+    /// unwind.
+    #[inline]
+    fn write_init_with(
+        write: impl FnOnce(T::Slots<'_>),
+        param: T::Param,
+    ) -> Pin<Self::Ptr<T::Initialized>>
+    where
+        T: PinnedInit + WriteUninit,
+    {
+        let res = crate::write_init_pinned::<T, _, Self, _>(write, |this| {
+            T::init_raw(this, param);
+            Ok::<(), Infallible>(())
+        });
+        match res {
+            Ok(this) => this,
+            Err(e) => match e {},
+        }
+    }
+
+    /// Allocates a new `T`, writes its fields using `write` and then tries to
+    /// initialize it using `param`.
+    ///
+    /// When the initialization fails, the partially initialized value is
+    /// dropped, the memory is freed and the error is returned.
+    #[inline]
+    fn try_write_init_with(
+        write: impl FnOnce(T::Slots<'_>),
+        param: T::Param,
+    ) -> Result<Pin<Self::Ptr<T::Initialized>>, T::Error>
+    where
+        T: TryPinnedInit + WriteUninit,
+    {
+        crate::write_init_pinned::<T, _, Self, _>(write, |this| T::try_init_raw(this, param))
+    }
+}
+
+/// An [`OwnedUniquePtr<[T]>`] that can allocate memory for a slice of `T`
+/// without initializing it.
+///
+/// This allows initializing slices with a length only known at runtime, every
+/// element is initialized in place:
+/// ```rust
+/// # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+/// use core::{mem::MaybeUninit, pin::Pin};
+/// use pinned_init::{prelude::*, ptr::AllocUninitSlice};
+///
+/// #[manual_init(pinned)]
+/// pub struct Conn {
+///     #[init]
+///     id: usize,
+/// }
+///
+/// impl PinnedInit for ConnUninit {
+///     type Initialized = Conn;
+///     type Param = usize;
+///
+///     fn init_raw(this: NeedsPinnedInit<Self>, id: usize) {
+///         let ConnOngoingInit { id: slot } = this.begin_init();
+///         slot.init(id);
+///     }
+/// }
+///
+/// let len = 16;
+/// let conns: Pin<Box<[Conn]>> = Box::<[ConnUninit]>::init_slice(
+///     len,
+///     |_| ConnUninit { id: MaybeUninit::uninit() },
+///     |slice| {
+///         // initialize the second half first.
+///         let (first, second) = slice.split_at(len / 2);
+///         for (i, conn) in second.into_iter().enumerate() {
+///             PinnedInit::init_raw(conn, len / 2 + i);
+///         }
+///         for (i, conn) in first.into_iter().enumerate() {
+///             PinnedInit::init_raw(conn, i);
+///         }
+///     },
+/// );
+/// assert_eq!(conns.len(), 16);
+/// assert_eq!(conns[9].id, 9);
+/// ```
+///
+/// # Safety
+///
+/// - [`Self::alloc_uninit_slice`] needs to return a new allocation that is
+///   valid for a slice of `len` elements.
+/// - [`Self::assume_init_slice_pinned`] and [`Self::transmute_slice_pinned`]
+///   must only change the type of the pointer, they must not move the elements.
 #[cfg(feature = "alloc")]
+pub unsafe trait AllocUninitSlice<T>: OwnedUniquePtr<[T]> {
+    /// Allocates memory for a slice of `len` elements without initializing it.
+    fn alloc_uninit_slice(len: usize) -> Pin<Self::Ptr<[MaybeUninit<T>]>>;
+
+    /// Converts the pointer to the now fully written slice.
+    ///
+    /// # Safety
+    ///
+    /// All elements of the slice behind `this` need to be written.
+    unsafe fn assume_init_slice_pinned(this: Pin<Self::Ptr<[MaybeUninit<T>]>>) -> Pin<Self>;
+
+    /// Transmute the elements of the slice behind this pointer while being
+    /// pinned.
+    ///
+    /// # Safety
+    ///
+    /// The caller needs to guarantee, that it is safe to transmute every `T`
+    /// to `U` (see [`OwnedUniquePtr::transmute_pointee_pinned`]).
+    unsafe fn transmute_slice_pinned<U>(this: Pin<Self>) -> Pin<Self::Ptr<[U]>>
+    where
+        T: TransmuteInto<U>;
+
+    /// Allocates a slice of `len` elements, moves `uninit(i)` into the `i`-th
+    /// element and then initializes the slice using `init`.
+    ///
+    /// When `uninit` or `init` panic, the written and initialized elements
+    /// are dropped and the memory is freed before the panic continues to
+    /// unwind.
+    #[inline]
+    fn init_slice(
+        len: usize,
+        uninit: impl FnMut(usize) -> T,
+        init: impl FnOnce(NeedsPinnedInitSlice<'_, T>),
+    ) -> Pin<Self::Ptr<[T::Initialized]>>
+    where
+        T: PinnedInit,
+    {
+        let res = crate::init_slice_pinned::<T, _, Self, _>(len, uninit, |this| {
+            init(this);
+            Ok::<(), Infallible>(())
+        });
+        match res {
+            Ok(this) => this,
+            Err(e) => match e {},
+        }
+    }
+
+    /// Allocates a slice of `len` elements, moves `uninit(i)` into the `i`-th
+    /// element and then initializes it using `param(i)`.
+    #[inline]
+    fn init_slice_with(
+        len: usize,
+        uninit: impl FnMut(usize) -> T,
+        mut param: impl FnMut(usize) -> T::Param,
+    ) -> Pin<Self::Ptr<[T::Initialized]>>
+    where
+        T: PinnedInit,
+    {
+        Self::init_slice(len, uninit, |this| {
+            for (i, elem) in this.into_iter().enumerate() {
+                T::init_raw(elem, param(i));
+            }
+        })
+    }
+
+    /// Allocates a slice of `len` elements, moves `uninit(i)` into the `i`-th
+    /// element and then tries to initialize it using `param(i)`.
+    ///
+    /// When the initialization of an element fails, the already initialized
+    /// elements are dropped, the memory is freed and the error is returned.
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    fn try_init_slice_with(
+        len: usize,
+        uninit: impl FnMut(usize) -> T,
+        mut param: impl FnMut(usize) -> T::Param,
+    ) -> Result<Pin<Self::Ptr<[T::Initialized]>>, T::Error>
+    where
+        T: TryPinnedInit,
+    {
+        crate::init_slice_pinned::<T, _, Self, _>(len, uninit, |this| {
+            for (i, elem) in this.into_iter().enumerate() {
+                T::try_init_raw(elem, param(i))?;
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(all(feature = "alloc", not(feature = "allocator_api")))]
 unsafe impl<T: ?Sized> OwnedUniquePtr<T> for alloc::boxed::Box<T> {
     type Ptr<U: ?Sized> = alloc::boxed::Box<U>;
 
@@ -80,3 +305,499 @@ unsafe impl<T: ?Sized> OwnedUniquePtr<T> for alloc::boxed::Box<T> {
         }
     }
 }
+
+#[cfg(all(feature = "alloc", not(feature = "allocator_api")))]
+unsafe impl<T> AllocUninit<T> for alloc::boxed::Box<T> {
+    #[inline]
+    fn alloc_uninit() -> Pin<Self::Ptr<MaybeUninit<T>>> {
+        alloc::boxed::Box::into_pin(alloc::boxed::Box::new_uninit())
+    }
+
+    #[inline]
+    unsafe fn assume_init_pinned(this: Pin<Self::Ptr<MaybeUninit<T>>>) -> Pin<Self> {
+        unsafe {
+            // SAFETY: we later repin the pointer and never move the data behind
+            // it. The caller guarantees that the value is initialized.
+            Pin::new_unchecked(Pin::into_inner_unchecked(this).assume_init())
+        }
+    }
+}
+
+#[cfg(all(feature = "alloc", not(feature = "allocator_api")))]
+unsafe impl<T> AllocUninitSlice<T> for alloc::boxed::Box<[T]> {
+    #[inline]
+    fn alloc_uninit_slice(len: usize) -> Pin<Self::Ptr<[MaybeUninit<T>]>> {
+        alloc::boxed::Box::into_pin(alloc::boxed::Box::new_uninit_slice(len))
+    }
+
+    #[inline]
+    unsafe fn assume_init_slice_pinned(this: Pin<Self::Ptr<[MaybeUninit<T>]>>) -> Pin<Self> {
+        unsafe {
+            // SAFETY: we later repin the pointer and never move the data behind
+            // it. The caller guarantees that the elements are initialized.
+            Pin::new_unchecked(Pin::into_inner_unchecked(this).assume_init())
+        }
+    }
+
+    #[inline]
+    unsafe fn transmute_slice_pinned<U>(this: Pin<Self>) -> Pin<Self::Ptr<[U]>>
+    where
+        T: TransmuteInto<U>,
+    {
+        #[cfg(not(feature = "std"))]
+        use alloc::boxed::Box;
+        unsafe {
+            // SAFETY: we later repin the pointer and never move the data behind it.
+            let this = Pin::into_inner_unchecked(this);
+            // this is safe, due to the requriements of this function
+            let this: Box<[U]> = Box::from_raw(Box::into_raw(this) as *mut [U]);
+            Pin::new_unchecked(this)
+        }
+    }
+}
+
+#[cfg(feature = "allocator_api")]
+unsafe impl<T: ?Sized, A: core::alloc::Allocator> OwnedUniquePtr<T> for alloc::boxed::Box<T, A> {
+    type Ptr<U: ?Sized> = alloc::boxed::Box<U, A>;
+
+    #[inline]
+    unsafe fn transmute_pointee_pinned<U>(this: Pin<Self>) -> Pin<Self::Ptr<U>>
+    where
+        T: TransmuteInto<U>,
+    {
+        #[cfg(not(feature = "std"))]
+        use alloc::boxed::Box;
+        unsafe {
+            // SAFETY: we later repin the pointer and never move the data behind it.
+            let this = Pin::into_inner_unchecked(this);
+            let (ptr, alloc) = Box::into_raw_with_allocator(this);
+            // this is safe, due to the requriements of this function
+            let this: Box<U, A> = Box::from_raw_in(ptr as *mut U, alloc);
+            Pin::new_unchecked(this)
+        }
+    }
+}
+
+#[cfg(feature = "allocator_api")]
+unsafe impl<T, A: core::alloc::Allocator + Default> AllocUninit<T> for alloc::boxed::Box<T, A> {
+    #[inline]
+    fn alloc_uninit() -> Pin<Self::Ptr<MaybeUninit<T>>> {
+        unsafe {
+            // SAFETY: the new allocation is unique and we never move the value.
+            Pin::new_unchecked(alloc::boxed::Box::new_uninit_in(A::default()))
+        }
+    }
+
+    #[inline]
+    unsafe fn assume_init_pinned(this: Pin<Self::Ptr<MaybeUninit<T>>>) -> Pin<Self> {
+        unsafe {
+            // SAFETY: we later repin the pointer and never move the data behind
+            // it. The caller guarantees that the value is initialized.
+            Pin::new_unchecked(Pin::into_inner_unchecked(this).assume_init())
+        }
+    }
+}
+
+#[cfg(feature = "allocator_api")]
+unsafe impl<T, A: core::alloc::Allocator + Default> AllocUninitSlice<T>
+    for alloc::boxed::Box<[T], A>
+{
+    #[inline]
+    fn alloc_uninit_slice(len: usize) -> Pin<Self::Ptr<[MaybeUninit<T>]>> {
+        unsafe {
+            // SAFETY: the new allocation is unique and we never move the elements.
+            Pin::new_unchecked(alloc::boxed::Box::new_uninit_slice_in(len, A::default()))
+        }
+    }
+
+    #[inline]
+    unsafe fn assume_init_slice_pinned(this: Pin<Self::Ptr<[MaybeUninit<T>]>>) -> Pin<Self> {
+        unsafe {
+            // SAFETY: we later repin the pointer and never move the data behind
+            // it. The caller guarantees that the elements are initialized.
+            Pin::new_unchecked(Pin::into_inner_unchecked(this).assume_init())
+        }
+    }
+
+    #[inline]
+    unsafe fn transmute_slice_pinned<U>(this: Pin<Self>) -> Pin<Self::Ptr<[U]>>
+    where
+        T: TransmuteInto<U>,
+    {
+        #[cfg(not(feature = "std"))]
+        use alloc::boxed::Box;
+        unsafe {
+            // SAFETY: we later repin the pointer and never move the data behind it.
+            let this = Pin::into_inner_unchecked(this);
+            let (ptr, alloc) = Box::into_raw_with_allocator(this);
+            // this is safe, due to the requriements of this function
+            let this: Box<[U], A> = Box::from_raw_in(ptr as *mut [U], alloc);
+            Pin::new_unchecked(this)
+        }
+    }
+}
+
+/// An [`Arc`] that is known to be unique, this allows mutable access to the
+/// value and thus implements [`OwnedUniquePtr<T>`].
+///
+/// After initialization use [`UniqueArc::share`] to convert it into a normal
+/// [`Arc`] that can be shared with other threads:
+/// ```rust
+/// # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+/// use core::{mem::MaybeUninit, pin::Pin};
+/// use pinned_init::{prelude::*, ptr::UniqueArc};
+/// use std::sync::Arc;
+///
+/// #[manual_init(pinned)]
+/// pub struct Counter {
+///     #[init]
+///     #[uninit = MaybeUninit::<u64>]
+///     count: u64,
+/// }
+///
+/// impl PinnedInit for CounterUninit {
+///     type Initialized = Counter;
+///     type Param = u64;
+///
+///     fn init_raw(this: NeedsPinnedInit<Self>, start: u64) {
+///         let CounterOngoingInit { count } = this.begin_init();
+///         count.init(start);
+///     }
+/// }
+///
+/// let uninit = CounterUninit {
+///     count: MaybeUninit::uninit(),
+/// };
+/// let counter = UniqueArc::pin(uninit).init_with(42);
+/// let counter: Pin<Arc<Counter>> = UniqueArc::share(counter);
+/// assert_eq!(counter.count, 42);
+/// ```
+///
+/// [`Arc`]: alloc::sync::Arc
+#[cfg(feature = "alloc")]
+#[repr(transparent)]
+pub struct UniqueArc<T: ?Sized>(alloc::sync::Arc<T>);
+
+#[cfg(feature = "alloc")]
+impl<T> UniqueArc<T> {
+    /// Allocates a new [`UniqueArc<T>`] containing `t`.
+    #[inline]
+    pub fn new(t: T) -> Self {
+        Self(alloc::sync::Arc::new(t))
+    }
+
+    /// Allocates a new pinned [`UniqueArc<T>`] containing `t`.
+    #[inline]
+    pub fn pin(t: T) -> Pin<Self> {
+        unsafe {
+            // SAFETY: we have the only reference to `t` and we never move it.
+            Pin::new_unchecked(Self::new(t))
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> UniqueArc<T> {
+    /// Converts this pinned [`UniqueArc<T>`] into a pinned [`Arc<T>`] that can
+    /// be shared.
+    ///
+    /// [`Arc<T>`]: alloc::sync::Arc
+    #[inline]
+    pub fn share(this: Pin<Self>) -> Pin<alloc::sync::Arc<T>> {
+        unsafe {
+            // SAFETY: the `Arc` will keep the value pinned, as it only hands out
+            // shared references.
+            Pin::new_unchecked(Pin::into_inner_unchecked(this).0)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> From<UniqueArc<T>> for alloc::sync::Arc<T> {
+    #[inline]
+    fn from(this: UniqueArc<T>) -> Self {
+        this.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> Deref for UniqueArc<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> DerefMut for UniqueArc<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe {
+            // SAFETY: we hold the only reference to this value, no one else is
+            // able to access it.
+            &mut *(alloc::sync::Arc::as_ptr(&self.0) as *mut T)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T: ?Sized> OwnedUniquePtr<T> for UniqueArc<T> {
+    type Ptr<U: ?Sized> = UniqueArc<U>;
+
+    #[inline]
+    unsafe fn transmute_pointee_pinned<U>(this: Pin<Self>) -> Pin<Self::Ptr<U>>
+    where
+        T: TransmuteInto<U>,
+    {
+        use alloc::sync::Arc;
+        unsafe {
+            // SAFETY: we later repin the pointer and never move the data behind it.
+            let this = Pin::into_inner_unchecked(this);
+            // this is safe, due to the requriements of this function
+            let this: UniqueArc<U> = UniqueArc(Arc::from_raw(Arc::into_raw(this.0) as *const U));
+            Pin::new_unchecked(this)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> AllocUninit<T> for UniqueArc<T> {
+    #[inline]
+    fn alloc_uninit() -> Pin<Self::Ptr<MaybeUninit<T>>> {
+        unsafe {
+            // SAFETY: the new allocation is unique and we never move the value.
+            Pin::new_unchecked(UniqueArc(alloc::sync::Arc::new_uninit()))
+        }
+    }
+
+    #[inline]
+    unsafe fn assume_init_pinned(this: Pin<Self::Ptr<MaybeUninit<T>>>) -> Pin<Self> {
+        unsafe {
+            // SAFETY: we later repin the pointer and never move the data behind
+            // it. The caller guarantees that the value is initialized.
+            let this = Pin::into_inner_unchecked(this);
+            Pin::new_unchecked(UniqueArc(this.0.assume_init()))
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> AllocUninitSlice<T> for UniqueArc<[T]> {
+    #[inline]
+    fn alloc_uninit_slice(len: usize) -> Pin<Self::Ptr<[MaybeUninit<T>]>> {
+        unsafe {
+            // SAFETY: the new allocation is unique and we never move the elements.
+            Pin::new_unchecked(UniqueArc(alloc::sync::Arc::new_uninit_slice(len)))
+        }
+    }
+
+    #[inline]
+    unsafe fn assume_init_slice_pinned(this: Pin<Self::Ptr<[MaybeUninit<T>]>>) -> Pin<Self> {
+        unsafe {
+            // SAFETY: we later repin the pointer and never move the data behind
+            // it. The caller guarantees that the elements are initialized.
+            let this = Pin::into_inner_unchecked(this);
+            Pin::new_unchecked(UniqueArc(this.0.assume_init()))
+        }
+    }
+
+    #[inline]
+    unsafe fn transmute_slice_pinned<U>(this: Pin<Self>) -> Pin<Self::Ptr<[U]>>
+    where
+        T: TransmuteInto<U>,
+    {
+        use alloc::sync::Arc;
+        unsafe {
+            // SAFETY: we later repin the pointer and never move the data behind it.
+            let this = Pin::into_inner_unchecked(this);
+            // this is safe, due to the requriements of this function
+            let this: UniqueArc<[U]> =
+                UniqueArc(Arc::from_raw(Arc::into_raw(this.0) as *const [U]));
+            Pin::new_unchecked(this)
+        }
+    }
+}
+
+/// An [`Rc`] that is known to be unique, this allows mutable access to the
+/// value and thus implements [`OwnedUniquePtr<T>`].
+///
+/// After initialization use [`UniqueRc::share`] to convert it into a normal
+/// [`Rc`] that can be shared on the current thread:
+/// ```rust
+/// # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+/// use core::{mem::MaybeUninit, pin::Pin};
+/// use pinned_init::{prelude::*, ptr::UniqueRc};
+/// use std::rc::Rc;
+///
+/// #[manual_init(pinned)]
+/// pub struct Counter {
+///     #[init]
+///     #[uninit = MaybeUninit::<u64>]
+///     count: u64,
+/// }
+///
+/// impl PinnedInit for CounterUninit {
+///     type Initialized = Counter;
+///     type Param = u64;
+///
+///     fn init_raw(this: NeedsPinnedInit<Self>, start: u64) {
+///         let CounterOngoingInit { count } = this.begin_init();
+///         count.init(start);
+///     }
+/// }
+///
+/// let uninit = CounterUninit {
+///     count: MaybeUninit::uninit(),
+/// };
+/// let counter = UniqueRc::pin(uninit).init_with(42);
+/// let counter: Pin<Rc<Counter>> = UniqueRc::share(counter);
+/// assert_eq!(counter.count, 42);
+/// ```
+///
+/// [`Rc`]: alloc::rc::Rc
+#[cfg(feature = "alloc")]
+#[repr(transparent)]
+pub struct UniqueRc<T: ?Sized>(alloc::rc::Rc<T>);
+
+#[cfg(feature = "alloc")]
+impl<T> UniqueRc<T> {
+    /// Allocates a new [`UniqueRc<T>`] containing `t`.
+    #[inline]
+    pub fn new(t: T) -> Self {
+        Self(alloc::rc::Rc::new(t))
+    }
+
+    /// Allocates a new pinned [`UniqueRc<T>`] containing `t`.
+    #[inline]
+    pub fn pin(t: T) -> Pin<Self> {
+        unsafe {
+            // SAFETY: we have the only reference to `t` and we never move it.
+            Pin::new_unchecked(Self::new(t))
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> UniqueRc<T> {
+    /// Converts this pinned [`UniqueRc<T>`] into a pinned [`Rc<T>`] that can
+    /// be shared.
+    ///
+    /// [`Rc<T>`]: alloc::rc::Rc
+    #[inline]
+    pub fn share(this: Pin<Self>) -> Pin<alloc::rc::Rc<T>> {
+        unsafe {
+            // SAFETY: the `Rc` will keep the value pinned, as it only hands out
+            // shared references.
+            Pin::new_unchecked(Pin::into_inner_unchecked(this).0)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> From<UniqueRc<T>> for alloc::rc::Rc<T> {
+    #[inline]
+    fn from(this: UniqueRc<T>) -> Self {
+        this.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> Deref for UniqueRc<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> DerefMut for UniqueRc<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe {
+            // SAFETY: we hold the only reference to this value, no one else is
+            // able to access it.
+            &mut *(alloc::rc::Rc::as_ptr(&self.0) as *mut T)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T: ?Sized> OwnedUniquePtr<T> for UniqueRc<T> {
+    type Ptr<U: ?Sized> = UniqueRc<U>;
+
+    #[inline]
+    unsafe fn transmute_pointee_pinned<U>(this: Pin<Self>) -> Pin<Self::Ptr<U>>
+    where
+        T: TransmuteInto<U>,
+    {
+        use alloc::rc::Rc;
+        unsafe {
+            // SAFETY: we later repin the pointer and never move the data behind it.
+            let this = Pin::into_inner_unchecked(this);
+            // this is safe, due to the requriements of this function
+            let this: UniqueRc<U> = UniqueRc(Rc::from_raw(Rc::into_raw(this.0) as *const U));
+            Pin::new_unchecked(this)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> AllocUninit<T> for UniqueRc<T> {
+    #[inline]
+    fn alloc_uninit() -> Pin<Self::Ptr<MaybeUninit<T>>> {
+        unsafe {
+            // SAFETY: the new allocation is unique and we never move the value.
+            Pin::new_unchecked(UniqueRc(alloc::rc::Rc::new_uninit()))
+        }
+    }
+
+    #[inline]
+    unsafe fn assume_init_pinned(this: Pin<Self::Ptr<MaybeUninit<T>>>) -> Pin<Self> {
+        unsafe {
+            // SAFETY: we later repin the pointer and never move the data behind
+            // it. The caller guarantees that the value is initialized.
+            let this = Pin::into_inner_unchecked(this);
+            Pin::new_unchecked(UniqueRc(this.0.assume_init()))
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> AllocUninitSlice<T> for UniqueRc<[T]> {
+    #[inline]
+    fn alloc_uninit_slice(len: usize) -> Pin<Self::Ptr<[MaybeUninit<T>]>> {
+        unsafe {
+            // SAFETY: the new allocation is unique and we never move the elements.
+            Pin::new_unchecked(UniqueRc(alloc::rc::Rc::new_uninit_slice(len)))
+        }
+    }
+
+    #[inline]
+    unsafe fn assume_init_slice_pinned(this: Pin<Self::Ptr<[MaybeUninit<T>]>>) -> Pin<Self> {
+        unsafe {
+            // SAFETY: we later repin the pointer and never move the data behind
+            // it. The caller guarantees that the elements are initialized.
+            let this = Pin::into_inner_unchecked(this);
+            Pin::new_unchecked(UniqueRc(this.0.assume_init()))
+        }
+    }
+
+    #[inline]
+    unsafe fn transmute_slice_pinned<U>(this: Pin<Self>) -> Pin<Self::Ptr<[U]>>
+    where
+        T: TransmuteInto<U>,
+    {
+        use alloc::rc::Rc;
+        unsafe {
+            // SAFETY: we later repin the pointer and never move the data behind it.
+            let this = Pin::into_inner_unchecked(this);
+            // this is safe, due to the requriements of this function
+            let this: UniqueRc<[U]> = UniqueRc(Rc::from_raw(Rc::into_raw(this.0) as *const [U]));
+            Pin::new_unchecked(this)
+        }
+    }
+}
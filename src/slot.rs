@@ -0,0 +1,124 @@
+//! Module providing [`Slot<'a, T>`], used to write the fields of an
+//! uninitialized value directly into its final allocation.
+//!
+//! The [`manual_init`] and [`pinned_init`] proc macro attributes generate a
+//! `{your-struct-name}Slots` struct containing a [`Slot`] for every field of
+//! the uninitialized variant of your struct. Use [`AllocUninit::write_init`]
+//! to allocate the memory and fill the slots:
+//! ```rust
+//! # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+//! use core::pin::Pin;
+//! use pinned_init::{prelude::*, ptr::AllocUninit};
+//!
+//! #[manual_init(pinned)]
+//! pub struct Buf {
+//!     data: [u8; 1 << 16],
+//!     #[init]
+//!     len: usize,
+//! }
+//!
+//! impl PinnedInit for BufUninit {
+//!     type Initialized = Buf;
+//!     type Param = ();
+//!
+//!     fn init_raw(this: NeedsPinnedInit<Self>, _: ()) {
+//!         let BufOngoingInit { data, len } = this.begin_init();
+//!         len.init(data.len());
+//!     }
+//! }
+//!
+//! // the buffer is never placed on the stack.
+//! let buf: Pin<Box<Buf>> = Box::<BufUninit>::write_init(|slots| {
+//!     let BufSlots { mut data, len } = slots;
+//!     unsafe {
+//!         // SAFETY: all bytes of the buffer are set to zero.
+//!         data.as_mut_ptr().write_bytes(0, 1);
+//!         data.assume_written();
+//!     }
+//!     len.uninit();
+//! });
+//! assert_eq!(buf.len, 1 << 16);
+//! ```
+//!
+//! Every slot needs to be written, when a slot was not written the
+//! already written fields are dropped and a panic is raised.
+//!
+//! [`manual_init`]: crate::manual_init
+//! [`pinned_init`]: crate::pinned_init
+//! [`AllocUninit::write_init`]: crate::ptr::AllocUninit::write_init
+
+use crate::static_uninit::StaticUninit;
+use core::{cell::Cell, marker::PhantomData, mem::MaybeUninit};
+
+/// A pointer to a not yet written field of an uninitialized value.
+///
+/// Writing to the slot consumes it, so every slot is written at most once.
+pub struct Slot<'a, T> {
+    ptr: *mut T,
+    flag: &'a Cell<bool>,
+    _phantom: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Slot<'a, T> {
+    /// Creates a new slot.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` needs to be valid for writes for the duration of `'a`.
+    /// - `flag` needs to track, if the value at `ptr` has been written.
+    #[doc(hidden)]
+    #[inline]
+    pub unsafe fn new_unchecked(ptr: *mut T, flag: &'a Cell<bool>) -> Self {
+        Self {
+            ptr,
+            flag,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Writes `value` into this slot.
+    #[inline]
+    pub fn write(self, value: T) {
+        unsafe {
+            // SAFETY: `ptr` is valid for writes.
+            self.ptr.write(value);
+        }
+        self.flag.set(true);
+    }
+
+    /// Get a raw mutable pointer to the (not yet written) value.
+    ///
+    /// Use this pointer to write the value piece by piece and call
+    /// [`Slot::assume_written`] afterwards.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr
+    }
+
+    /// Marks this slot as written.
+    ///
+    /// # Safety
+    ///
+    /// The value behind this slot needs to have been fully written via
+    /// [`Slot::as_mut_ptr`].
+    #[inline]
+    pub unsafe fn assume_written(self) {
+        self.flag.set(true);
+    }
+}
+
+impl<'a, T> Slot<'a, MaybeUninit<T>> {
+    /// Leaves this slot uninitialized, it will be initialized later.
+    #[inline]
+    pub fn uninit(self) {
+        self.flag.set(true);
+    }
+}
+
+impl<'a, T> Slot<'a, StaticUninit<T, false>> {
+    /// Leaves this slot uninitialized, it will be initialized later.
+    #[inline]
+    pub fn uninit(self) {
+        self.flag.set(true);
+    }
+}
@@ -0,0 +1,166 @@
+//! Module providing a slot on the stack, that can be used to initialize values
+//! without allocating them on the heap.
+//!
+//! The easiest way to use a [`StackInit<T>`] is via the [`stack_init!`] macro:
+//! ```rust
+//! # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+//! use core::{mem::MaybeUninit, pin::Pin};
+//! use pinned_init::prelude::*;
+//!
+//! #[manual_init(pinned)]
+//! pub struct Counter {
+//!     #[init]
+//!     #[uninit = MaybeUninit::<u64>]
+//!     count: u64,
+//! }
+//!
+//! impl PinnedInit for CounterUninit {
+//!     type Initialized = Counter;
+//!     type Param = u64;
+//!
+//!     fn init_raw(this: NeedsPinnedInit<Self>, start: u64) {
+//!         let CounterOngoingInit { count } = this.begin_init();
+//!         count.init(start);
+//!     }
+//! }
+//!
+//! stack_init!(let counter = CounterUninit { count: MaybeUninit::uninit() }, 42);
+//! let counter: Pin<&mut Counter> = counter;
+//! assert_eq!(counter.count, 42);
+//! ```
+//!
+//! [`stack_init!`]: crate::stack_init
+
+use crate::{
+    needs_init::NeedsPinnedInit, private::BeginPinnedInit, transmute::TransmuteInto, PinnedInit,
+    TryPinnedInit,
+};
+use core::{convert::Infallible, marker::PhantomPinned, mem::MaybeUninit, pin::Pin, ptr};
+
+/// A slot on the stack, that can hold an initialized `T`.
+///
+/// The value inside of the slot is dropped, when the slot is dropped. Because
+/// a [`StackInit<T>`] is `!Unpin`, it needs to be pinned before it can be used.
+/// Prefer the [`stack_init!`] macro, which takes care of that.
+///
+/// [`stack_init!`]: crate::stack_init
+pub struct StackInit<T> {
+    value: MaybeUninit<T>,
+    is_init: bool,
+    _pin: PhantomPinned,
+}
+
+impl<T> StackInit<T> {
+    /// Creates a new empty slot.
+    #[inline]
+    pub fn uninit() -> Self {
+        Self {
+            value: MaybeUninit::uninit(),
+            is_init: false,
+            _pin: PhantomPinned,
+        }
+    }
+
+    /// Moves `uninit` into this slot and initializes it using `param`.
+    ///
+    /// When the slot already holds a value, that value is dropped first.
+    #[inline]
+    pub fn init<U>(self: Pin<&mut Self>, uninit: U, param: U::Param) -> Pin<&mut T>
+    where
+        U: PinnedInit<Initialized = T>,
+    {
+        match self.init_inner(uninit, |this| {
+            U::init_raw(this, param);
+            Ok::<(), Infallible>(())
+        }) {
+            Ok(this) => this,
+            Err(e) => match e {},
+        }
+    }
+
+    /// Moves `uninit` into this slot and tries to initialize it using `param`.
+    ///
+    /// When the slot already holds a value, that value is dropped first. When
+    /// the initialization fails, the slot is left empty.
+    #[inline]
+    pub fn try_init<U>(
+        self: Pin<&mut Self>,
+        uninit: U,
+        param: U::Param,
+    ) -> Result<Pin<&mut T>, U::Error>
+    where
+        U: TryPinnedInit<Initialized = T>,
+    {
+        self.init_inner(uninit, |this| U::try_init_raw(this, param))
+    }
+
+    fn init_inner<U, E>(
+        self: Pin<&mut Self>,
+        uninit: U,
+        init: impl FnOnce(NeedsPinnedInit<'_, U>) -> Result<(), E>,
+    ) -> Result<Pin<&mut T>, E>
+    where
+        U: BeginPinnedInit + TransmuteInto<T>,
+    {
+        let this = unsafe {
+            // SAFETY: we never move the value out of the slot.
+            self.get_unchecked_mut()
+        };
+        this.clear();
+        let ptr = this.value.as_mut_ptr() as *mut U;
+        unsafe {
+            // SAFETY: `U` and `T` have the same layout, because `U: TransmuteInto<T>`.
+            ptr.write(uninit);
+            // SAFETY: we have unique access to the slot, which is pinned. When
+            // the initialization fails, the value was dropped and the slot stays
+            // empty.
+            crate::init_in_place(Pin::new_unchecked(&mut *ptr), init)?;
+        }
+        this.is_init = true;
+        Ok(unsafe {
+            // SAFETY: the value has been fully initialized and is pinned.
+            Pin::new_unchecked(&mut *(U::transmute_ptr(ptr) as *mut T))
+        })
+    }
+
+    /// Drops the value inside of this slot, if it holds one.
+    fn clear(&mut self) {
+        if self.is_init {
+            self.is_init = false;
+            unsafe {
+                // SAFETY: the value is initialized and was never moved.
+                ptr::drop_in_place(self.value.as_mut_ptr())
+            }
+        }
+    }
+}
+
+impl<T> Drop for StackInit<T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Pins the given uninitialized value on the stack and initializes it.
+///
+/// `stack_init!(let name = uninit)` initializes `uninit` with the default
+/// parameter, use `stack_init!(let name = uninit, param)` to supply a
+/// parameter. Afterwards `name` is a `Pin<&mut T>` pointing to the initialized
+/// value, which is dropped at the end of the current scope.
+///
+/// See the [`stack`](crate::stack) module for an example.
+#[macro_export]
+macro_rules! stack_init {
+    (let $var:ident = $uninit:expr) => {
+        $crate::stack_init!(let $var = $uninit, $crate::DefaultParam::default_param())
+    };
+    (let $var:ident = $uninit:expr, $param:expr) => {
+        let mut $var = $crate::stack::StackInit::uninit();
+        let $var = unsafe {
+            // SAFETY: the slot is shadowed and thus can never be moved again.
+            ::core::pin::Pin::new_unchecked(&mut $var)
+        };
+        let $var = $var.init($uninit, $param);
+    };
+}
@@ -0,0 +1,192 @@
+//! Module providing a field type that is statically known to be uninitialized
+//! in the uninitialized form of a struct and initialized in its initialized
+//! form.
+//!
+//! A [`StaticUninit<T>`] field behaves like a `T` (via [`Deref`] and
+//! [`DerefMut`]), while a [`StaticUninit<T, false>`] field can only be
+//! initialized via [`NeedsInit::init`] or [`NeedsPinnedInit::init`]:
+//! ```rust
+//! # #![feature(generic_associated_types, const_ptr_offset_from, const_refs_to_cell)]
+//! use pinned_init::{prelude::*, static_uninit::StaticUninit};
+//!
+//! #[manual_init(pinned)]
+//! pub struct Buf {
+//!     data: [u8; 16],
+//!     #[init]
+//!     len: StaticUninit<usize>,
+//! }
+//!
+//! impl PinnedInit for BufUninit {
+//!     type Initialized = Buf;
+//!     type Param = ();
+//!
+//!     fn init_raw(this: NeedsPinnedInit<Self>, _: ()) {
+//!         let BufOngoingInit { data, len } = this.begin_init();
+//!         len.init(data.len());
+//!     }
+//! }
+//!
+//! let uninit = BufUninit {
+//!     data: [0; 16],
+//!     len: StaticUninit::uninit(),
+//! };
+//! let buf = Box::pin(uninit).init();
+//! assert_eq!(*buf.len, 16);
+//! ```
+//!
+//! [`NeedsInit::init`]: crate::needs_init::NeedsInit
+//! [`NeedsPinnedInit::init`]: crate::needs_init::NeedsPinnedInit
+
+use crate::{private::AsUninit, private::PartialInit, transmute::TransmuteInto, PinnedDeinit};
+use core::{
+    mem::MaybeUninit,
+    ops::{Deref, DerefMut},
+    ptr,
+};
+
+/// A field wrapper that is uninitialized when `INIT == false` and initialized
+/// when `INIT == true`.
+///
+/// Use `StaticUninit<T>` as the field type in your struct, the uninitialized
+/// form of your struct will then contain a `StaticUninit<T, false>`.
+#[repr(transparent)]
+pub struct StaticUninit<T, const INIT: bool = true>(MaybeUninit<T>);
+
+impl<T> StaticUninit<T, false> {
+    /// Creates a new uninitialized value.
+    #[inline]
+    pub const fn uninit() -> Self {
+        Self(MaybeUninit::uninit())
+    }
+
+    /// Get a raw const pointer to the (uninitialized) value.
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        self.0.as_ptr()
+    }
+
+    /// Get a raw mutable pointer to the (uninitialized) value.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.0.as_mut_ptr()
+    }
+}
+
+impl<T> StaticUninit<T> {
+    /// Creates a new initialized value.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self(MaybeUninit::new(value))
+    }
+
+    /// Returns the contained value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        let this = core::mem::ManuallyDrop::new(self);
+        unsafe {
+            // SAFETY: `INIT == true`, so the value is initialized and `this` is
+            // never dropped.
+            this.0.as_ptr().read()
+        }
+    }
+}
+
+impl<T> Deref for StaticUninit<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe {
+            // SAFETY: `INIT == true`, so the value is initialized.
+            self.0.assume_init_ref()
+        }
+    }
+}
+
+impl<T> DerefMut for StaticUninit<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe {
+            // SAFETY: `INIT == true`, so the value is initialized.
+            self.0.assume_init_mut()
+        }
+    }
+}
+
+impl<T, const INIT: bool> Drop for StaticUninit<T, INIT> {
+    #[inline]
+    fn drop(&mut self) {
+        if INIT {
+            unsafe {
+                // SAFETY: `INIT == true`, so the value is initialized.
+                ptr::drop_in_place(self.0.as_mut_ptr())
+            }
+        }
+    }
+}
+
+// SAFETY: both types are `repr(transparent)` wrappers of `MaybeUninit<T>`. The
+// caller needs to ensure that the value is initialized.
+unsafe impl<T> TransmuteInto<StaticUninit<T>> for StaticUninit<T, false> {
+    #[inline]
+    unsafe fn transmute_ptr(this: *const Self) -> *const StaticUninit<T> {
+        this as *const StaticUninit<T>
+    }
+}
+
+// SAFETY: dropping the value leaves an uninitialized `StaticUninit<T, false>`
+// behind.
+unsafe impl<T> PinnedDeinit<StaticUninit<T>> for StaticUninit<T, false> {
+    #[inline]
+    unsafe fn deinit_raw(this: *mut StaticUninit<T>) {
+        unsafe {
+            // SAFETY: the caller guarantees that the value is valid and that it
+            // is not used as a `StaticUninit<T>` again.
+            ptr::drop_in_place(this)
+        }
+    }
+}
+
+// SAFETY: the uninitialized form has the same layout.
+unsafe impl<T> AsUninit for StaticUninit<T> {
+    type Uninit = StaticUninit<T, false>;
+}
+
+// SAFETY: the flag is only set, when the value has been written.
+unsafe impl<T> PartialInit for StaticUninit<T, false> {
+    type Flags = <MaybeUninit<T> as PartialInit>::Flags;
+
+    #[inline]
+    fn __new_flags() -> Self::Flags {
+        MaybeUninit::<T>::__new_flags()
+    }
+
+    #[inline]
+    unsafe fn __is_init(this: *const Self, flags: &Self::Flags) -> bool {
+        unsafe {
+            // SAFETY: `Self` is a `repr(transparent)` wrapper of `MaybeUninit<T>`.
+            MaybeUninit::<T>::__is_init(this as *const MaybeUninit<T>, flags)
+        }
+    }
+
+    #[inline]
+    unsafe fn __is_uninit(this: *const Self, flags: &Self::Flags) -> bool {
+        unsafe {
+            // SAFETY: `Self` is a `repr(transparent)` wrapper of `MaybeUninit<T>`.
+            MaybeUninit::<T>::__is_uninit(this as *const MaybeUninit<T>, flags)
+        }
+    }
+
+    #[inline]
+    fn __set_init(flags: &Self::Flags) {
+        MaybeUninit::<T>::__set_init(flags)
+    }
+
+    #[inline]
+    unsafe fn __drop_partial(this: *mut Self, flags: &Self::Flags) {
+        unsafe {
+            // SAFETY: `Self` is a `repr(transparent)` wrapper of `MaybeUninit<T>`.
+            MaybeUninit::<T>::__drop_partial(this as *mut MaybeUninit<T>, flags)
+        }
+    }
+}
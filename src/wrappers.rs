@@ -0,0 +1,551 @@
+//! Support for initializing the contents of wrapper types from [`core`].
+//!
+//! [`UnsafeCell<T>`], [`Cell<T>`] and [`ManuallyDrop<T>`] forward their
+//! initialization to their contents. Only [`UnsafeCell<T>`] structurally pins
+//! its contents, [`Cell<T>`] can move them out through a shared reference and
+//! [`ManuallyDrop<T>`] frees them without dropping them, so those two only
+//! support [`Init`] and [`TryInit`].
+//! [`PhantomPinned`] and [`PhantomData<T>`] are always initialized.
+//!
+//! [`Option<T>`] initializes its contents when it is `Some` and stays `None`
+//! otherwise. `Option<T::Uninit>` and `Option<T>` may use different niches to
+//! encode `None`, so only `Option`s that store `None` in a separate tag are
+//! supported, this is checked at compile time.
+
+use crate::{
+    needs_init::{NeedsInit, NeedsPinnedInit},
+    private::{__field_ptr, AsUninit, BeginInit, BeginPinnedInit, PartialInit},
+    transmute::TransmuteInto,
+    Init, PinnedDeinit, PinnedInit, TryInit, TryPinnedInit,
+};
+use core::{
+    cell::{Cell, UnsafeCell},
+    marker::{PhantomData, PhantomPinned},
+    mem::{align_of, offset_of, size_of, ManuallyDrop},
+};
+
+macro_rules! transparent_init {
+    ($($wrapper:ident { drop_contents: $drop:literal }),* $(,)?) => {
+        $(
+            // SAFETY: `$wrapper<T>` is `repr(transparent)`, so `$wrapper<T::Uninit>`
+            // has the same layout as `$wrapper<T>`.
+            unsafe impl<T: AsUninit> AsUninit for $wrapper<T> {
+                type Uninit = $wrapper<T::Uninit>;
+            }
+
+            // SAFETY: `$wrapper<T>` is `repr(transparent)` and `T` can be
+            // transmuted to `U`.
+            unsafe impl<U, T: TransmuteInto<U>> TransmuteInto<$wrapper<U>> for $wrapper<T> {
+                #[inline]
+                unsafe fn transmute_ptr(this: *const Self) -> *const $wrapper<U> {
+                    this as *const $wrapper<U>
+                }
+            }
+
+            // SAFETY: `$wrapper<T>` is `repr(transparent)`, so the flags of `T`
+            // track the initialization of the wrapper.
+            unsafe impl<T: PartialInit> PartialInit for $wrapper<T> {
+                type Flags = T::Flags;
+
+                #[inline]
+                fn __new_flags() -> Self::Flags {
+                    T::__new_flags()
+                }
+
+                #[inline]
+                unsafe fn __is_init(this: *const Self, flags: &Self::Flags) -> bool {
+                    unsafe {
+                        // SAFETY: `$wrapper<T>` is `repr(transparent)`.
+                        T::__is_init(this as *const T, flags)
+                    }
+                }
+
+                #[inline]
+                unsafe fn __is_uninit(this: *const Self, flags: &Self::Flags) -> bool {
+                    unsafe {
+                        // SAFETY: `$wrapper<T>` is `repr(transparent)`.
+                        T::__is_uninit(this as *const T, flags)
+                    }
+                }
+
+                #[inline]
+                fn __set_init(flags: &Self::Flags) {
+                    T::__set_init(flags)
+                }
+
+                #[inline]
+                unsafe fn __drop_partial(this: *mut Self, flags: &Self::Flags) {
+                    if $drop {
+                        unsafe {
+                            // SAFETY: `$wrapper<T>` is `repr(transparent)` and the
+                            // caller guarantees that `this` is valid for dropping.
+                            T::__drop_partial(this as *mut T, flags)
+                        }
+                    }
+                }
+            }
+
+            impl<T: PartialInit> BeginInit for $wrapper<T> {
+                type OngoingInit<'init>
+                    = NeedsInit<'init, T>
+                where
+                    Self: 'init;
+
+                #[inline]
+                unsafe fn __begin_init<'init>(
+                    &'init mut self,
+                    flags: &'init Self::Flags,
+                ) -> Self::OngoingInit<'init>
+                where
+                    Self: 'init,
+                {
+                    unsafe {
+                        // SAFETY: `$wrapper<T>` is `repr(transparent)` and the
+                        // caller guarantees, that the wrapper changes its type.
+                        NeedsInit::new_unchecked(&mut *(self as *mut Self as *mut T), flags)
+                    }
+                }
+            }
+
+            impl<T: Init> Init for $wrapper<T> {
+                type Initialized = $wrapper<T::Initialized>;
+                type Param = T::Param;
+
+                #[inline]
+                fn init_raw(this: NeedsInit<Self>, param: Self::Param) {
+                    T::init_raw(this.begin_init(), param)
+                }
+            }
+
+            impl<T: TryInit> TryInit for $wrapper<T> {
+                type Initialized = $wrapper<T::Initialized>;
+                type Param = T::Param;
+                type Error = T::Error;
+
+                #[inline]
+                fn try_init_raw(this: NeedsInit<Self>, param: Self::Param) -> Result<(), T::Error> {
+                    T::try_init_raw(this.begin_init(), param)
+                }
+            }
+        )*
+    };
+}
+
+transparent_init! {
+    UnsafeCell { drop_contents: true },
+    Cell { drop_contents: true },
+    // a `ManuallyDrop<T>` never drops its contents, not even when their
+    // initialization fails.
+    ManuallyDrop { drop_contents: false },
+}
+
+macro_rules! transparent_pinned_init {
+    ($($wrapper:ident),* $(,)?) => {
+        $(
+            impl<T: PartialInit> BeginPinnedInit for $wrapper<T> {
+                type OngoingInit<'init>
+                    = NeedsPinnedInit<'init, T>
+                where
+                    Self: 'init;
+
+                #[inline]
+                unsafe fn __begin_init<'init>(
+                    this: *mut Self,
+                    flags: &'init Self::Flags,
+                ) -> Self::OngoingInit<'init>
+                where
+                    Self: 'init,
+                {
+                    unsafe {
+                        // SAFETY: `$wrapper<T>` is `repr(transparent)`, the
+                        // contents are structurally pinned, because the
+                        // wrapper only hands out raw pointers to them and
+                        // drops them together with itself. The caller
+                        // guarantees, that the wrapper changes its type.
+                        NeedsPinnedInit::from_raw(this as *mut T, flags)
+                    }
+                }
+            }
+
+            impl<T: PinnedInit> PinnedInit for $wrapper<T> {
+                type Initialized = $wrapper<T::Initialized>;
+                type Param = T::Param;
+
+                #[inline]
+                fn init_raw(this: NeedsPinnedInit<Self>, param: Self::Param) {
+                    T::init_raw(this.begin_init(), param)
+                }
+            }
+
+            impl<T: TryPinnedInit> TryPinnedInit for $wrapper<T> {
+                type Initialized = $wrapper<T::Initialized>;
+                type Param = T::Param;
+                type Error = T::Error;
+
+                #[inline]
+                fn try_init_raw(this: NeedsPinnedInit<Self>, param: Self::Param) -> Result<(), T::Error> {
+                    T::try_init_raw(this.begin_init(), param)
+                }
+            }
+        )*
+    };
+}
+
+// the contents of a `Cell<T>` can be moved out through a shared reference with
+// `Cell::take`, `Cell::replace` and `Cell::swap` and a `ManuallyDrop<T>` frees
+// its contents without dropping them, so neither of them structurally pins its
+// contents.
+transparent_pinned_init! {
+    UnsafeCell,
+}
+
+macro_rules! transparent_deinit {
+    ($($wrapper:ident),* $(,)?) => {
+        $(
+            // SAFETY: `$wrapper<T>` is `repr(transparent)`, so deinitializing
+            // the contents deinitializes the wrapper.
+            unsafe impl<U: PinnedDeinit<T>, T> PinnedDeinit<$wrapper<T>> for $wrapper<U> {
+                #[inline]
+                unsafe fn deinit_raw(this: *mut $wrapper<T>) {
+                    unsafe {
+                        // SAFETY: `$wrapper<T>` is `repr(transparent)` and the
+                        // caller upholds the requirements for the contents.
+                        U::deinit_raw(this as *mut T)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+// only pinned contents are deinitialized, so this is not implemented for
+// `Cell<T>` and `ManuallyDrop<T>` (see above).
+transparent_deinit! {
+    UnsafeCell,
+}
+
+/// Checks that `Option<T>` can be transmuted into `Option<U>`. `T` and `U` may
+/// have different niches (e.g. `bool` and `MaybeUninit<bool>`), so this is only
+/// the case, when neither `Option` encodes `None` in a niche of its contents.
+/// Then `None` is encoded in a separate tag and the layouts of the `Option`s
+/// only depend on the layouts of `T` and `U`.
+struct OptionLayout<T, U>(PhantomData<(T, U)>);
+
+impl<T, U> OptionLayout<T, U> {
+    const CHECK: () = {
+        if size_of::<T>() != size_of::<U>() || align_of::<T>() != align_of::<U>() {
+            panic!("The contents of the `Option`s do not have the same layout.");
+        }
+        // when `None` is encoded in a niche, the `Option` has the same size as
+        // its contents.
+        if size_of::<Option<T>>() == size_of::<T>() || size_of::<Option<U>>() == size_of::<U>() {
+            panic!(
+                "`Option<T>` is only supported, when it does not encode `None` in a niche of `T`."
+            );
+        }
+        if size_of::<Option<T>>() != size_of::<Option<U>>()
+            || offset_of!(Option<T>, Some.0) != offset_of!(Option<U>, Some.0)
+        {
+            panic!("The layouts of the `Option`s are not identical.");
+        }
+    };
+}
+
+/// Returns a pointer to the contents of the `Option` at `this`, when it is
+/// `Some`.
+///
+/// # Safety
+///
+/// `this` needs to be valid for reads.
+#[inline]
+unsafe fn contents<T>(this: *mut Option<T>) -> Option<*mut T> {
+    unsafe {
+        // SAFETY: the caller guarantees that `this` is valid for reads, the
+        // reference is only used to find the variant and the contents.
+        (*this).as_ref().map(|contents| __field_ptr(this, contents))
+    }
+}
+
+// SAFETY: `Option<T::Uninit>` has the same layout as `Option<T>`, this is
+// checked by `OptionLayout` before the `Option` is used.
+unsafe impl<T: AsUninit> AsUninit for Option<T> {
+    type Uninit = Option<T::Uninit>;
+}
+
+// SAFETY: `T` can be transmuted to `U` and `OptionLayout` checks that the
+// `Option`s have the same layout.
+unsafe impl<U, T: TransmuteInto<U>> TransmuteInto<Option<U>> for Option<T> {
+    #[inline]
+    unsafe fn transmute_ptr(this: *const Self) -> *const Option<U> {
+        let () = OptionLayout::<T, U>::CHECK;
+        this as *const Option<U>
+    }
+}
+
+// SAFETY: the flags of `T` track the initialization of the contents, `None` is
+// always initialized.
+unsafe impl<T: PartialInit> PartialInit for Option<T> {
+    type Flags = T::Flags;
+
+    #[inline]
+    fn __new_flags() -> Self::Flags {
+        T::__new_flags()
+    }
+
+    #[inline]
+    unsafe fn __is_init(this: *const Self, flags: &Self::Flags) -> bool {
+        unsafe {
+            // SAFETY: the caller guarantees that `this` is valid.
+            match contents(this as *mut Self) {
+                Some(contents) => T::__is_init(contents, flags),
+                None => true,
+            }
+        }
+    }
+
+    #[inline]
+    unsafe fn __is_uninit(this: *const Self, flags: &Self::Flags) -> bool {
+        unsafe {
+            // SAFETY: the caller guarantees that `this` is valid.
+            match contents(this as *mut Self) {
+                Some(contents) => T::__is_uninit(contents, flags),
+                None => true,
+            }
+        }
+    }
+
+    #[inline]
+    fn __set_init(flags: &Self::Flags) {
+        T::__set_init(flags)
+    }
+
+    #[inline]
+    unsafe fn __drop_partial(this: *mut Self, flags: &Self::Flags) {
+        unsafe {
+            // SAFETY: the caller guarantees that `this` is valid for dropping.
+            if let Some(contents) = contents(this) {
+                T::__drop_partial(contents, flags)
+            }
+        }
+    }
+}
+
+impl<T: PartialInit> BeginPinnedInit for Option<T> {
+    type OngoingInit<'init>
+        = Option<NeedsPinnedInit<'init, T>>
+    where
+        Self: 'init;
+
+    #[inline]
+    unsafe fn __begin_init<'init>(
+        this: *mut Self,
+        flags: &'init Self::Flags,
+    ) -> Self::OngoingInit<'init>
+    where
+        Self: 'init,
+    {
+        unsafe {
+            // SAFETY: an `Option` structurally pins its contents (see
+            // `Pin::as_pin_mut`). Wrappers in `T` that do not pin their own
+            // contents (e.g. `Cell<T>`) do not implement `BeginPinnedInit`, so
+            // the contents cannot be initialized through them. The caller
+            // guarantees, that the `Option` changes its type.
+            contents(this).map(|contents| NeedsPinnedInit::from_raw(contents, flags))
+        }
+    }
+}
+
+impl<T: PartialInit> BeginInit for Option<T> {
+    type OngoingInit<'init>
+        = Option<NeedsInit<'init, T>>
+    where
+        Self: 'init;
+
+    #[inline]
+    unsafe fn __begin_init<'init>(
+        &'init mut self,
+        flags: &'init Self::Flags,
+    ) -> Self::OngoingInit<'init>
+    where
+        Self: 'init,
+    {
+        self.as_mut().map(|contents| unsafe {
+            // SAFETY: the caller guarantees, that the `Option` changes its
+            // type.
+            NeedsInit::new_unchecked(contents, flags)
+        })
+    }
+}
+
+impl<T: PinnedInit> PinnedInit for Option<T> {
+    type Initialized = Option<T::Initialized>;
+    type Param = T::Param;
+
+    #[inline]
+    fn init_raw(this: NeedsPinnedInit<Self>, param: Self::Param) {
+        // `None` is already initialized, the parameter is not needed.
+        if let Some(contents) = this.begin_init() {
+            T::init_raw(contents, param)
+        }
+    }
+}
+
+impl<T: TryPinnedInit> TryPinnedInit for Option<T> {
+    type Initialized = Option<T::Initialized>;
+    type Param = T::Param;
+    type Error = T::Error;
+
+    #[inline]
+    fn try_init_raw(this: NeedsPinnedInit<Self>, param: Self::Param) -> Result<(), T::Error> {
+        match this.begin_init() {
+            Some(contents) => T::try_init_raw(contents, param),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: Init> Init for Option<T> {
+    type Initialized = Option<T::Initialized>;
+    type Param = T::Param;
+
+    #[inline]
+    fn init_raw(this: NeedsInit<Self>, param: Self::Param) {
+        if let Some(contents) = this.begin_init() {
+            T::init_raw(contents, param)
+        }
+    }
+}
+
+impl<T: TryInit> TryInit for Option<T> {
+    type Initialized = Option<T::Initialized>;
+    type Param = T::Param;
+    type Error = T::Error;
+
+    #[inline]
+    fn try_init_raw(this: NeedsInit<Self>, param: Self::Param) -> Result<(), T::Error> {
+        match this.begin_init() {
+            Some(contents) => T::try_init_raw(contents, param),
+            None => Ok(()),
+        }
+    }
+}
+
+// SAFETY: deinitializing the contents of `Some` deinitializes the `Option` and
+// `OptionLayout` checks that the `Option`s have the same layout.
+unsafe impl<U: PinnedDeinit<T>, T> PinnedDeinit<Option<T>> for Option<U> {
+    #[inline]
+    unsafe fn deinit_raw(this: *mut Option<T>) {
+        let () = OptionLayout::<U, T>::CHECK;
+        unsafe {
+            // SAFETY: the caller upholds the requirements for the contents.
+            if let Some(contents) = contents(this) {
+                U::deinit_raw(contents)
+            }
+        }
+    }
+}
+
+macro_rules! trivial_init {
+    ($({$($generics:tt)*} $ty:ty),* $(,)?) => {
+        $(
+            // SAFETY: the type is a ZST without any invariants.
+            unsafe impl<$($generics)*> AsUninit for $ty {
+                type Uninit = $ty;
+            }
+
+            // SAFETY: transmuting a type to itself is always permitted.
+            unsafe impl<$($generics)*> TransmuteInto<$ty> for $ty {
+                #[inline]
+                unsafe fn transmute_ptr(this: *const Self) -> *const $ty {
+                    this
+                }
+            }
+
+            // SAFETY: the type is a ZST, it is always initialized and dropping
+            // it does nothing.
+            unsafe impl<$($generics)*> PartialInit for $ty {
+                type Flags = ();
+
+                #[inline]
+                fn __new_flags() -> Self::Flags {}
+
+                #[inline]
+                unsafe fn __is_init(_: *const Self, _: &Self::Flags) -> bool {
+                    true
+                }
+
+                #[inline]
+                unsafe fn __is_uninit(_: *const Self, _: &Self::Flags) -> bool {
+                    true
+                }
+
+                #[inline]
+                fn __set_init(_: &Self::Flags) {}
+
+                #[inline]
+                unsafe fn __drop_partial(_: *mut Self, _: &Self::Flags) {}
+            }
+
+            // SAFETY: the type is a ZST, dropping it does nothing.
+            unsafe impl<$($generics)*> PinnedDeinit<$ty> for $ty {
+                #[inline]
+                unsafe fn deinit_raw(_: *mut $ty) {}
+            }
+
+            impl<$($generics)*> BeginPinnedInit for $ty {
+                type OngoingInit<'init>
+                    = ()
+                where
+                    Self: 'init;
+
+                #[inline]
+                unsafe fn __begin_init<'init>(
+                    _: *mut Self,
+                    _: &'init Self::Flags,
+                ) -> Self::OngoingInit<'init>
+                where
+                    Self: 'init,
+                {
+                }
+            }
+
+            impl<$($generics)*> BeginInit for $ty {
+                type OngoingInit<'init>
+                    = ()
+                where
+                    Self: 'init;
+
+                #[inline]
+                unsafe fn __begin_init<'init>(
+                    &'init mut self,
+                    _: &'init Self::Flags,
+                ) -> Self::OngoingInit<'init>
+                where
+                    Self: 'init,
+                {
+                }
+            }
+
+            impl<$($generics)*> PinnedInit for $ty {
+                type Initialized = $ty;
+                type Param = ();
+
+                #[inline]
+                fn init_raw(_: NeedsPinnedInit<Self>, _: ()) {}
+            }
+
+            impl<$($generics)*> Init for $ty {
+                type Initialized = $ty;
+                type Param = ();
+
+                #[inline]
+                fn init_raw(_: NeedsInit<Self>, _: ()) {}
+            }
+        )*
+    };
+}
+
+trivial_init! {
+    {} PhantomPinned,
+    {T: ?Sized} PhantomData<T>,
+}